@@ -1,20 +1,23 @@
 use crate::{
     domain::events::{AppEvent, Command, CommandResult, Event},
-    infra::event_emmiter::{EventEmitterError, EventListener},
+    infra::{
+        error::Error,
+        event_emmiter::{EventEmitterError, EventListener},
+    },
 };
 use thiserror::Error;
-use tracing::{error, info, warn};
+use tracing::{info, warn};
 
 use super::service::ClientService;
 
 #[derive(Error, Debug)]
 pub enum ClientServiceError {
-    #[error("Failed to remove client: {0}")]
-    RemoveClientError(String),
-    #[error("Failed to setup or update client: {0}")]
-    SetupClientError(String),
-    #[error("Command handling error: {0}")]
-    CommandError(String),
+    #[error("{0}: {1:?}")]
+    RemoveClientError(String, Error),
+    #[error("{0}: {1:?}")]
+    SetupClientError(String, Error),
+    #[error("{0}: {1:?}")]
+    CommandError(String, Error),
     #[error("Failed to send command result: {0}")]
     SendResultError(String),
 }
@@ -25,6 +28,18 @@ impl From<ClientServiceError> for EventEmitterError {
     }
 }
 
+impl ClientServiceError {
+    /// The message for the client that sent the failed command.
+    fn client_message(&self) -> String {
+        match self {
+            ClientServiceError::RemoveClientError(_, e)
+            | ClientServiceError::SetupClientError(_, e)
+            | ClientServiceError::CommandError(_, e) => e.client_message(),
+            ClientServiceError::SendResultError(_) => "Something went wrong".to_string(),
+        }
+    }
+}
+
 #[async_trait::async_trait]
 impl EventListener for ClientService {
     async fn handle_event(&self, event: AppEvent) -> Result<(), EventEmitterError> {
@@ -43,10 +58,10 @@ impl ClientService {
         match event {
             Event::ClientDisconnected(client_id) => {
                 self.remove_client(client_id).await.map_err(|e| {
-                    ClientServiceError::RemoveClientError(format!(
-                        "Failed to remove client {}: {:?}",
-                        client_id, e
-                    ))
+                    ClientServiceError::RemoveClientError(
+                        format!("Failed to remove client {}", client_id),
+                        e,
+                    )
                 })?;
                 info!("Client {} disconnected", client_id);
             }
@@ -54,10 +69,10 @@ impl ClientService {
                 self.setup_or_update_client(client_id, tx)
                     .await
                     .map_err(|e| {
-                        ClientServiceError::SetupClientError(format!(
-                            "Failed to setup client {}: {:?}",
-                            client_id, e
-                        ))
+                        ClientServiceError::SetupClientError(
+                            format!("Failed to setup client {}", client_id),
+                            e,
+                        )
                     })?;
                 info!("Client {} connected", client_id);
             }
@@ -73,9 +88,10 @@ impl ClientService {
         command: Command,
         result_sender: tokio::sync::mpsc::Sender<CommandResult>,
     ) -> Result<(), ClientServiceError> {
-        let result = self.handle_command(command).await.map_err(|e| {
-            ClientServiceError::CommandError(format!("Error handling command: {:?}", e))
-        })?;
+        let result = self.handle_command(command).await.unwrap_or_else(|e| {
+            warn!("Error handling command: {}", e);
+            CommandResult::Error(e.client_message())
+        });
 
         result_sender.send(result).await.map_err(|e| {
             ClientServiceError::SendResultError(format!("Failed to send command result: {:?}", e))
@@ -86,25 +102,59 @@ impl ClientService {
 
     async fn handle_command(&self, command: Command) -> Result<CommandResult, ClientServiceError> {
         match command {
-            Command::BroadcastGameState(room_code, game_state) => self
-                .broadcast_game_state(room_code, game_state)
+            Command::BroadcastGameState(room_code, game_view) => self
+                .broadcast_game_state(room_code, *game_view)
                 .await
                 .map_err(|e| {
-                    ClientServiceError::CommandError(format!(
-                        "Failed to broadcast game state: {:?}",
-                        e
-                    ))
+                    ClientServiceError::CommandError(
+                        "Failed to broadcast game state".to_string(),
+                        e,
+                    )
                 }),
             Command::SetClientRoomCode(client_id, room_code) => {
                 self.join_room(client_id, room_code).await.map_err(|e| {
-                    ClientServiceError::CommandError(format!(
-                        "Failed to set client room code: {:?}",
-                        e
-                    ))
+                    ClientServiceError::CommandError(
+                        "Failed to set client room code".to_string(),
+                        e,
+                    )
+                })
+            }
+            Command::ClearClientRoomCode(client_id) => {
+                self.leave_room(client_id).await.map_err(|e| {
+                    ClientServiceError::CommandError(
+                        "Failed to clear client room code".to_string(),
+                        e,
+                    )
+                })
+            }
+            Command::BroadcastMessage(room_code, message) => self
+                .broadcast_message(room_code, message)
+                .await
+                .map_err(|e| {
+                    ClientServiceError::CommandError("Failed to broadcast message".to_string(), e)
+                }),
+            Command::WatchLobby(client_id, watching) => {
+                self.watch_lobby(client_id, watching).await.map_err(|e| {
+                    ClientServiceError::CommandError(
+                        "Failed to update lobby subscription".to_string(),
+                        e,
+                    )
                 })
             }
+            Command::BroadcastLobby(rooms) => self.broadcast_lobby(rooms).await.map_err(|e| {
+                ClientServiceError::CommandError("Failed to broadcast lobby".to_string(), e)
+            }),
+            Command::SendToClient(client_id, message) => self
+                .send_to_client(client_id, message)
+                .await
+                .map(|_| CommandResult::MessageSent(client_id))
+                .map_err(|e| {
+                    ClientServiceError::CommandError(
+                        format!("Failed to send message to client {}", client_id),
+                        e,
+                    )
+                }),
             _ => Ok(CommandResult::NotHandled),
         }
     }
 }
-
@@ -8,7 +8,9 @@ use crate::{
     domain::{
         client::{Client, ClientServiceTrait},
         events::{CommandResult, Event, Topic},
-        game::game::Game,
+        game::view::GameView,
+        message::ServerMessage,
+        room::RoomSummary,
     },
     infra::{error::Error, event_emmiter::EventEmitter},
 };
@@ -46,7 +48,7 @@ impl ClientService {
     pub async fn setup_or_update_client(
         &self,
         client_id: u16,
-        tx: UnboundedSender<Game>,
+        tx: UnboundedSender<ServerMessage>,
     ) -> Result<CommandResult, Error> {
         let mut clients = self.clients.lock().await;
 
@@ -66,6 +68,8 @@ impl ClientService {
         if let Some(client_arc) = self.clients.lock().await.remove(&id) {
             let client = client_arc.lock().await;
             let room_code = client.get_room_code();
+            self.event_emitter
+                .emit_event(Topic::Matchmaking, Event::ClientRemoved(id, None))?;
             self.event_emitter
                 .emit_event(Topic::RoomService, Event::ClientRemoved(id, room_code))?;
         } else {
@@ -92,6 +96,15 @@ impl ClientService {
         Ok(CommandResult::ClientRoomCodeSet(client_id, room_code))
     }
 
+    pub async fn leave_room(&self, client_id: u16) -> Result<CommandResult, Error> {
+        let client_arc = self.find_client(client_id).await?;
+        let mut client = client_arc.lock().await;
+
+        client.leave_room();
+
+        Ok(CommandResult::ClientRoomCodeCleared(client_id))
+    }
+
     pub async fn get_clients_in_room(
         &self,
         room_code: &str,
@@ -113,21 +126,70 @@ impl ClientService {
     pub async fn broadcast_game_state(
         &self,
         room_code: String,
-        game_state: Arc<Mutex<Game>>,
+        game_view: GameView,
+    ) -> Result<CommandResult, Error> {
+        self.broadcast_message(room_code, ServerMessage::GameState(Box::new(game_view)))
+            .await
+    }
+
+    pub async fn broadcast_message(
+        &self,
+        room_code: String,
+        message: ServerMessage,
     ) -> Result<CommandResult, Error> {
         let clients_in_room = self.get_clients_in_room(&room_code).await?;
-        let game_state = game_state.lock().await;
 
         for client_arc in clients_in_room {
             let client = client_arc.lock().await;
 
-            client.send_message(&game_state).await?;
+            client.send_message(message.clone()).await?;
         }
 
         Ok(CommandResult::BroadcastDone(
             "Broadcast successful".to_string(),
         ))
     }
+
+    pub async fn send_to_client(
+        &self,
+        client_id: u16,
+        message: ServerMessage,
+    ) -> Result<(), Error> {
+        let client_arc = self.find_client(client_id).await?;
+        let client = client_arc.lock().await;
+        client.send_message(message).await
+    }
+
+    pub async fn watch_lobby(
+        &self,
+        client_id: u16,
+        watching: bool,
+    ) -> Result<CommandResult, Error> {
+        let client_arc = self.find_client(client_id).await?;
+        let mut client = client_arc.lock().await;
+
+        client.set_watching_lobby(watching);
+
+        Ok(CommandResult::LobbyWatchUpdated(client_id, watching))
+    }
+
+    /// Sends the room browser listing to every client watching the lobby.
+    pub async fn broadcast_lobby(&self, rooms: Vec<RoomSummary>) -> Result<CommandResult, Error> {
+        for client_arc in self.clients.lock().await.values() {
+            let client = client_arc.lock().await;
+            if client.is_watching_lobby() {
+                client
+                    .send_message(ServerMessage::Lobby {
+                        rooms: rooms.clone(),
+                    })
+                    .await?;
+            }
+        }
+
+        Ok(CommandResult::BroadcastDone(
+            "Lobby broadcast successful".to_string(),
+        ))
+    }
 }
 
 #[async_trait]
@@ -143,7 +205,7 @@ impl ClientServiceTrait for ClientService {
     async fn setup_or_update_client(
         &self,
         client_id: u16,
-        tx: UnboundedSender<Game>,
+        tx: UnboundedSender<ServerMessage>,
     ) -> Result<CommandResult, Error> {
         self.setup_or_update_client(client_id, tx).await
     }
@@ -156,6 +218,10 @@ impl ClientServiceTrait for ClientService {
         self.join_room(client_id, room_code).await
     }
 
+    async fn leave_room(&self, client_id: u16) -> Result<CommandResult, Error> {
+        self.leave_room(client_id).await
+    }
+
     async fn get_clients_in_room(&self, room_code: &str) -> Result<Vec<Arc<Mutex<Client>>>, Error> {
         self.get_clients_in_room(room_code).await
     }
@@ -163,8 +229,28 @@ impl ClientServiceTrait for ClientService {
     async fn broadcast_game_state(
         &self,
         room_code: String,
-        game_state: Arc<Mutex<Game>>,
+        game_view: GameView,
+    ) -> Result<CommandResult, Error> {
+        self.broadcast_game_state(room_code, game_view).await
+    }
+
+    async fn broadcast_message(
+        &self,
+        room_code: String,
+        message: ServerMessage,
     ) -> Result<CommandResult, Error> {
-        self.broadcast_game_state(room_code, game_state).await
+        self.broadcast_message(room_code, message).await
+    }
+
+    async fn send_to_client(&self, client_id: u16, message: ServerMessage) -> Result<(), Error> {
+        self.send_to_client(client_id, message).await
+    }
+
+    async fn watch_lobby(&self, client_id: u16, watching: bool) -> Result<CommandResult, Error> {
+        self.watch_lobby(client_id, watching).await
+    }
+
+    async fn broadcast_lobby(&self, rooms: Vec<RoomSummary>) -> Result<CommandResult, Error> {
+        self.broadcast_lobby(rooms).await
     }
 }
@@ -2,7 +2,11 @@ use crate::infra::event_emmiter::EventEmitterError;
 use axum::extract::ws::{Message, WebSocket};
 use futures::SinkExt;
 use futures::StreamExt;
-use std::sync::Arc;
+use std::{
+    net::IpAddr,
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
 use tokio::sync::broadcast;
 use tokio::sync::mpsc::{unbounded_channel, UnboundedSender};
 use tokio_stream::wrappers::UnboundedReceiverStream;
@@ -11,9 +15,18 @@ use crate::{
     domain::{
         client::ClientServiceTrait,
         events::{AppEvent, Command, CommandResult, Event, Topic},
-        game::game::Game,
-        message::{MessageType, WsMessage},
+        game::{
+            compact::{CardEncoding, EncodedCard},
+            game::{GameMode, Move},
+            player::PlayerId,
+        },
+        message::{
+            ChatPayload, ClientMessage, IncomingMessage, JoinPayload, KickPayload, LockPayload,
+            MutePayload, QuickMatchPayload, ReadyPayload, RoomPayload, ServerMessage,
+            SetModePayload, VotePayload, WatchLobbyPayload,
+        },
         room::RoomServiceTrait,
+        vote::VoteKind,
     },
     infra::event_emmiter::{EventEmitter, EventEmitterTrait, EventListener},
 };
@@ -42,9 +55,16 @@ where
         }
     }
 
-    pub async fn start(&self, client_id: u16, ws: WebSocket) {
+    /// Serves a client's socket. `peer` is the address it connected from.
+    pub async fn start(
+        &self,
+        client_id: u16,
+        peer: Option<IpAddr>,
+        ws: WebSocket,
+        encoding: CardEncoding,
+    ) {
         let (ws_tx, ws_rx) = ws.split();
-        let (tx, rx) = unbounded_channel::<Game>();
+        let (tx, rx) = unbounded_channel::<ServerMessage>();
         let rx = UnboundedReceiverStream::new(rx);
 
         if let Err(e) = self.setup_client(client_id, tx).await {
@@ -52,8 +72,8 @@ where
             return;
         }
 
-        let reader_task = self.read_from_ws(ws_rx, client_id);
-        let writer_task = self.write_to_ws(rx, ws_tx);
+        let reader_task = self.read_from_ws(ws_rx, client_id, peer);
+        let writer_task = self.write_to_ws(rx, ws_tx, encoding);
 
         tokio::select! {
             result = reader_task => {
@@ -72,7 +92,7 @@ where
     async fn setup_client(
         &self,
         client_id: u16,
-        tx: UnboundedSender<Game>,
+        tx: UnboundedSender<ServerMessage>,
     ) -> Result<(), EventEmitterError> {
         self.event_emitter.emit_event(
             Topic::ClientService,
@@ -84,11 +104,12 @@ where
         &self,
         mut ws_rx: impl StreamExt<Item = Result<Message, axum::Error>> + Unpin,
         client_id: u16,
+        peer: Option<IpAddr>,
     ) -> Result<(), EventEmitterError> {
         while let Some(result) = ws_rx.next().await {
             match result {
                 Ok(msg) => {
-                    if let Err(e) = self.handle_incoming_message(msg, client_id).await {
+                    if let Err(e) = self.handle_incoming_message(msg, client_id, peer).await {
                         tracing::error!(
                             "Error handling message from client {}: {:?}",
                             client_id,
@@ -113,34 +134,98 @@ where
         &self,
         msg: Message,
         client_id: u16,
+        peer: Option<IpAddr>,
     ) -> Result<(), EventEmitterError> {
         match msg {
             Message::Text(text) => {
                 if text.trim().is_empty() {
                     return Ok(());
                 }
-                let message: WsMessage = serde_json::from_str(&text).map_err(|e| {
-                    EventEmitterError::SendError(format!("Failed to parse message: {}", e))
-                })?;
-                let message_type = MessageType::from_ws_message(message.clone()).map_err(|e| {
-                    EventEmitterError::SendError(format!("Invalid message type: {}", e))
-                })?;
+                let IncomingMessage {
+                    request_id,
+                    message,
+                } = match IncomingMessage::parse(&text) {
+                    Ok(message) => message,
+                    Err(invalid) => {
+                        return self
+                            .send(client_id, ServerMessage::InvalidMessage(invalid))
+                            .await;
+                    }
+                };
 
-                match message_type {
-                    MessageType::Join(message) => {
-                        self.handle_join_message(client_id, message).await
+                let result = match message {
+                    ClientMessage::Join(payload) => {
+                        self.handle_join_message(client_id, peer, payload).await
                     }
-                    MessageType::Move(message) => {
-                        self.handle_move_message(client_id, message).await
+                    ClientMessage::Move(game_move) => {
+                        self.handle_move_message(client_id, game_move).await
                     }
-                    MessageType::Request(message) => {
-                        self.handle_request_message(client_id, message).await
+                    ClientMessage::Request(RoomPayload { room_code }) => {
+                        self.handle_request_message(client_id, room_code).await
                     }
-                    _ => {
-                        tracing::warn!("Unknown message type: {:?}", message_type);
-                        Ok(())
+                    ClientMessage::Leave(RoomPayload { room_code }) => {
+                        self.handle_leave_message(client_id, room_code).await
                     }
-                }
+                    ClientMessage::Reset(RoomPayload { room_code }) => {
+                        self.handle_reset_message(client_id, room_code).await
+                    }
+                    ClientMessage::Ready(ReadyPayload { room_code, ready }) => {
+                        self.handle_ready_message(client_id, room_code, ready).await
+                    }
+                    ClientMessage::Start(RoomPayload { room_code }) => {
+                        self.handle_start_message(client_id, room_code).await
+                    }
+                    ClientMessage::Kick(KickPayload {
+                        room_code,
+                        player_id,
+                    }) => {
+                        self.handle_kick_message(client_id, room_code, player_id)
+                            .await
+                    }
+                    ClientMessage::SetMode(SetModePayload {
+                        room_code,
+                        mode,
+                        target_score,
+                    }) => {
+                        self.handle_set_mode_message(client_id, room_code, mode, target_score)
+                            .await
+                    }
+                    ClientMessage::Lock(LockPayload { room_code, locked }) => {
+                        self.handle_lock_message(client_id, room_code, locked).await
+                    }
+                    ClientMessage::Chat(ChatPayload { room_code, message }) => {
+                        self.handle_chat_message(client_id, room_code, message)
+                            .await
+                    }
+                    ClientMessage::Mute(MutePayload {
+                        room_code,
+                        player_id,
+                        muted,
+                    }) => {
+                        self.handle_mute_message(client_id, room_code, player_id, muted)
+                            .await
+                    }
+                    ClientMessage::Hint(RoomPayload { room_code }) => {
+                        self.handle_hint_message(client_id, room_code).await
+                    }
+                    ClientMessage::Vote(VotePayload { room_code, vote }) => {
+                        self.handle_vote_message(client_id, room_code, vote).await
+                    }
+                    ClientMessage::WatchLobby(WatchLobbyPayload { watching }) => {
+                        self.handle_watch_lobby_message(client_id, watching).await
+                    }
+                    ClientMessage::QuickMatch(payload) => {
+                        self.handle_quick_match_message(client_id, payload).await
+                    }
+                    ClientMessage::CancelQuickMatch => {
+                        self.handle_cancel_quick_match_message(client_id).await
+                    }
+                    ClientMessage::Ping => {
+                        return self.handle_ping_message(client_id, request_id).await
+                    }
+                };
+
+                self.respond(client_id, request_id, result).await
             }
             _ => Ok(()),
         }
@@ -149,49 +234,337 @@ where
     async fn handle_join_message(
         &self,
         client_id: u16,
-        message: WsMessage,
-    ) -> Result<(), EventEmitterError> {
+        peer: Option<IpAddr>,
+        payload: JoinPayload,
+    ) -> Result<CommandResult, EventEmitterError> {
         self.event_emitter
             .emit_command(
                 Topic::RoomService,
-                Command::RequestPlayerJoin(client_id, message),
+                Command::RequestPlayerJoin(client_id, payload, peer),
             )
             .await
-            .map(|_| ())
     }
 
     async fn handle_move_message(
         &self,
         client_id: u16,
-        message: WsMessage,
-    ) -> Result<(), EventEmitterError> {
+        game_move: Move,
+    ) -> Result<CommandResult, EventEmitterError> {
         self.event_emitter
-            .emit_command(Topic::RoomService, Command::PlayerMove(client_id, message))
+            .emit_command(
+                Topic::RoomService,
+                Command::PlayerMove(client_id, game_move),
+            )
             .await
-            .map(|_| ())
     }
 
     async fn handle_request_message(
         &self,
         client_id: u16,
-        message: WsMessage,
-    ) -> Result<(), EventEmitterError> {
+        room_code: String,
+    ) -> Result<CommandResult, EventEmitterError> {
+        self.event_emitter
+            .emit_command(
+                Topic::RoomService,
+                Command::RequestCards(client_id, room_code),
+            )
+            .await
+    }
+
+    async fn handle_leave_message(
+        &self,
+        client_id: u16,
+        room_code: String,
+    ) -> Result<CommandResult, EventEmitterError> {
+        self.event_emitter
+            .emit_command(
+                Topic::RoomService,
+                Command::RemovePlayerFromRoom(client_id, room_code),
+            )
+            .await
+    }
+
+    async fn handle_reset_message(
+        &self,
+        client_id: u16,
+        room_code: String,
+    ) -> Result<CommandResult, EventEmitterError> {
+        self.event_emitter
+            .emit_command(Topic::RoomService, Command::ResetGame(client_id, room_code))
+            .await
+    }
+
+    async fn handle_ready_message(
+        &self,
+        client_id: u16,
+        room_code: String,
+        ready: bool,
+    ) -> Result<CommandResult, EventEmitterError> {
+        self.event_emitter
+            .emit_command(
+                Topic::RoomService,
+                Command::SetPlayerReady(client_id, room_code, ready),
+            )
+            .await
+    }
+
+    async fn handle_start_message(
+        &self,
+        client_id: u16,
+        room_code: String,
+    ) -> Result<CommandResult, EventEmitterError> {
+        self.event_emitter
+            .emit_command(Topic::RoomService, Command::StartGame(client_id, room_code))
+            .await
+    }
+
+    async fn handle_kick_message(
+        &self,
+        client_id: u16,
+        room_code: String,
+        target: PlayerId,
+    ) -> Result<CommandResult, EventEmitterError> {
+        self.event_emitter
+            .emit_command(
+                Topic::RoomService,
+                Command::KickPlayer(client_id, room_code, target),
+            )
+            .await
+    }
+
+    async fn handle_set_mode_message(
+        &self,
+        client_id: u16,
+        room_code: String,
+        mode: GameMode,
+        target_score: Option<i64>,
+    ) -> Result<CommandResult, EventEmitterError> {
+        self.event_emitter
+            .emit_command(
+                Topic::RoomService,
+                Command::SetGameMode(client_id, room_code, mode, target_score),
+            )
+            .await
+    }
+
+    async fn handle_vote_message(
+        &self,
+        client_id: u16,
+        room_code: String,
+        vote: VoteKind,
+    ) -> Result<CommandResult, EventEmitterError> {
+        self.event_emitter
+            .emit_command(
+                Topic::RoomService,
+                Command::CastVote(client_id, room_code, vote),
+            )
+            .await
+    }
+
+    async fn handle_hint_message(
+        &self,
+        client_id: u16,
+        room_code: String,
+    ) -> Result<CommandResult, EventEmitterError> {
+        self.event_emitter
+            .emit_command(
+                Topic::RoomService,
+                Command::RequestHint(client_id, room_code),
+            )
+            .await
+    }
+
+    async fn handle_lock_message(
+        &self,
+        client_id: u16,
+        room_code: String,
+        locked: bool,
+    ) -> Result<CommandResult, EventEmitterError> {
+        self.event_emitter
+            .emit_command(
+                Topic::RoomService,
+                Command::LockRoom(client_id, room_code, locked),
+            )
+            .await
+    }
+
+    async fn handle_chat_message(
+        &self,
+        client_id: u16,
+        room_code: String,
+        message: String,
+    ) -> Result<CommandResult, EventEmitterError> {
         self.event_emitter
             .emit_command(
                 Topic::RoomService,
-                Command::RequestCards(client_id, message),
+                Command::SendChatMessage(client_id, room_code, message),
+            )
+            .await
+    }
+
+    async fn handle_mute_message(
+        &self,
+        client_id: u16,
+        room_code: String,
+        target: PlayerId,
+        muted: bool,
+    ) -> Result<CommandResult, EventEmitterError> {
+        self.event_emitter
+            .emit_command(
+                Topic::RoomService,
+                Command::MutePlayer(client_id, room_code, target, muted),
+            )
+            .await
+    }
+
+    /// Subscribes the client to room browser updates, sending the current
+    /// listing right away.
+    async fn handle_watch_lobby_message(
+        &self,
+        client_id: u16,
+        watching: bool,
+    ) -> Result<CommandResult, EventEmitterError> {
+        let result = self
+            .event_emitter
+            .emit_command(
+                Topic::ClientService,
+                Command::WatchLobby(client_id, watching),
             )
+            .await?;
+
+        if watching {
+            if let CommandResult::PublicRooms(rooms) = self
+                .event_emitter
+                .emit_command(Topic::RoomService, Command::ListPublicRooms)
+                .await?
+            {
+                self.send(client_id, ServerMessage::Lobby { rooms }).await?;
+            }
+        }
+
+        Ok(result)
+    }
+
+    async fn handle_quick_match_message(
+        &self,
+        client_id: u16,
+        payload: QuickMatchPayload,
+    ) -> Result<CommandResult, EventEmitterError> {
+        self.event_emitter
+            .emit_command(
+                Topic::Matchmaking,
+                Command::JoinMatchmaking(client_id, payload),
+            )
+            .await
+    }
+
+    async fn handle_cancel_quick_match_message(
+        &self,
+        client_id: u16,
+    ) -> Result<CommandResult, EventEmitterError> {
+        self.event_emitter
+            .emit_command(Topic::Matchmaking, Command::LeaveMatchmaking(client_id))
             .await
-            .map(|_| ())
+    }
+
+    async fn handle_ping_message(
+        &self,
+        client_id: u16,
+        request_id: Option<String>,
+    ) -> Result<(), EventEmitterError> {
+        let server_time = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("Time went backwards")
+            .as_millis() as u64;
+
+        self.send(
+            client_id,
+            ServerMessage::Pong {
+                request_id,
+                server_time,
+            },
+        )
+        .await
+    }
+
+    /// Translates the outcome of a client's command into the message sent back to
+    /// that client. Successful commands are only acknowledged when the client
+    /// attached a `request_id`.
+    async fn respond(
+        &self,
+        client_id: u16,
+        request_id: Option<String>,
+        result: Result<CommandResult, EventEmitterError>,
+    ) -> Result<(), EventEmitterError> {
+        let reply = match result {
+            Ok(CommandResult::PlayerMoveInvalid(reason)) => {
+                tracing::info!("Rejected move from client {}: {}", client_id, reason);
+                ServerMessage::MoveRejected {
+                    request_id,
+                    reason,
+                    message: reason.to_string(),
+                }
+            }
+            Ok(CommandResult::JoinRejected(room_code, reason)) => ServerMessage::JoinError {
+                request_id,
+                room_code,
+                reason,
+                message: reason.to_string(),
+            },
+            Ok(CommandResult::PlayerReJoined(_)) => {
+                self.send(
+                    client_id,
+                    ServerMessage::Notice {
+                        message: "Welcome back! Your score has been restored".to_string(),
+                    },
+                )
+                .await?;
+                match request_id {
+                    Some(request_id) => ServerMessage::Ack { request_id },
+                    None => return Ok(()),
+                }
+            }
+            Ok(CommandResult::Error(message)) => ServerMessage::Error {
+                request_id,
+                message,
+            },
+            Ok(_) => match request_id {
+                Some(request_id) => ServerMessage::Ack { request_id },
+                None => return Ok(()),
+            },
+            Err(e) => {
+                tracing::error!("Failed to handle message from client {}: {}", client_id, e);
+                ServerMessage::Error {
+                    request_id,
+                    message: "Something went wrong".to_string(),
+                }
+            }
+        };
+
+        self.send(client_id, reply).await
+    }
+
+    async fn send(&self, client_id: u16, message: ServerMessage) -> Result<(), EventEmitterError> {
+        self.client_service
+            .send_to_client(client_id, message)
+            .await
+            .map_err(|e| EventEmitterError::SendError(e.to_string()))
     }
 
     async fn write_to_ws(
         &self,
-        mut rx: UnboundedReceiverStream<Game>,
+        mut rx: UnboundedReceiverStream<ServerMessage>,
         mut ws_tx: impl futures::Sink<Message, Error = axum::Error> + Unpin,
+        encoding: CardEncoding,
     ) -> Result<(), EventEmitterError> {
-        while let Some(game) = rx.next().await {
-            let msg = serde_json::to_string(&game)
+        while let Some(message) = rx.next().await {
+            let serialized = match encoding {
+                CardEncoding::Full => serde_json::to_string(&message),
+                CardEncoding::Compact => {
+                    serde_json::to_string(&message.map_cards(EncodedCard::from))
+                }
+            };
+            let msg = serialized
                 .map(Message::Text)
                 .unwrap_or_else(|_| Message::Text("MESSAGE_SERIALIZATION_ERROR".to_string()));
 
@@ -0,0 +1,105 @@
+use crate::{
+    domain::events::{AppEvent, Command, CommandResult, Event},
+    infra::{
+        error::Error,
+        event_emmiter::{EventEmitterError, EventListener},
+    },
+};
+use thiserror::Error;
+use tracing::{info, warn};
+
+use super::service::MatchmakingService;
+
+#[derive(Error, Debug)]
+pub enum MatchmakingServiceError {
+    #[error("{0}: {1:?}")]
+    QueueError(String, Error),
+    #[error("Failed to send command result: {0}")]
+    SendResultError(String),
+}
+
+impl From<MatchmakingServiceError> for EventEmitterError {
+    fn from(error: MatchmakingServiceError) -> Self {
+        EventEmitterError::SendError(error.to_string())
+    }
+}
+
+impl MatchmakingServiceError {
+    /// The message for the client that sent the failed command.
+    fn client_message(&self) -> String {
+        match self {
+            MatchmakingServiceError::QueueError(_, e) => e.client_message(),
+            MatchmakingServiceError::SendResultError(_) => "Something went wrong".to_string(),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl EventListener for MatchmakingService {
+    async fn handle_event(&self, event: AppEvent) -> Result<(), EventEmitterError> {
+        match event {
+            AppEvent::EventOccurred(e) => self.handle_event_occurred(e).await,
+            AppEvent::CommandReceived(command, result_sender) => {
+                self.handle_received_command(command, result_sender).await?
+            }
+        }
+        Ok(())
+    }
+}
+
+impl MatchmakingService {
+    async fn handle_event_occurred(&self, event: Event) {
+        match event {
+            Event::ClientRemoved(client_id, _) => {
+                self.remove_client(client_id).await;
+                info!("Removed client {} from the matchmaking queue", client_id);
+            }
+            _ => {
+                warn!("Unhandled event: {:?}", event);
+            }
+        }
+    }
+
+    async fn handle_received_command(
+        &self,
+        command: Command,
+        result_sender: tokio::sync::mpsc::Sender<CommandResult>,
+    ) -> Result<(), MatchmakingServiceError> {
+        let result = self.handle_command(command).await.unwrap_or_else(|e| {
+            warn!("{}", e);
+            CommandResult::Error(e.client_message())
+        });
+        result_sender.send(result).await.map_err(|e| {
+            MatchmakingServiceError::SendResultError(format!(
+                "Failed to send command result: {:?}",
+                e
+            ))
+        })?;
+        Ok(())
+    }
+
+    async fn handle_command(
+        &self,
+        command: Command,
+    ) -> Result<CommandResult, MatchmakingServiceError> {
+        match command {
+            Command::JoinMatchmaking(client_id, payload) => {
+                self.handle_enqueue(client_id, payload).await.map_err(|e| {
+                    MatchmakingServiceError::QueueError(
+                        format!("Failed to queue client {}", client_id),
+                        e,
+                    )
+                })
+            }
+            Command::LeaveMatchmaking(client_id) => {
+                self.handle_cancel(client_id).await.map_err(|e| {
+                    MatchmakingServiceError::QueueError(
+                        format!("Failed to cancel matchmaking for client {}", client_id),
+                        e,
+                    )
+                })
+            }
+            _ => Ok(CommandResult::NotHandled),
+        }
+    }
+}
@@ -0,0 +1,165 @@
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use tokio::sync::Mutex;
+use tracing::warn;
+
+use crate::{
+    domain::{
+        events::{Command, CommandResult, Topic},
+        game::config::GameConfig,
+        matchmaking::{MatchRequest, MatchmakingQueue},
+        message::{JoinPayload, QuickMatchPayload, ServerMessage},
+        room::RoomSettings,
+    },
+    infra::{error::Error, event_emmiter::EventEmitter},
+};
+
+const MATCHMAKING_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Clone)]
+pub struct MatchmakingService {
+    queue: Arc<Mutex<MatchmakingQueue>>,
+    pub(super) event_emitter: Arc<EventEmitter>,
+}
+
+impl MatchmakingService {
+    pub fn new(event_emitter: Arc<EventEmitter>) -> Self {
+        Self {
+            queue: Arc::new(Mutex::new(MatchmakingQueue::new())),
+            event_emitter,
+        }
+    }
+
+    pub async fn handle_enqueue(
+        &self,
+        client_id: u16,
+        payload: QuickMatchPayload,
+    ) -> Result<CommandResult, Error> {
+        let QuickMatchPayload {
+            mode,
+            players,
+            player_username,
+        } = payload;
+
+        self.queue.lock().await.enqueue(MatchRequest {
+            client_id,
+            player_username: player_username.trim().to_string(),
+            mode,
+            players,
+            queued_at: Instant::now(),
+        });
+        self.process_queue().await;
+
+        Ok(CommandResult::MatchmakingQueued(client_id))
+    }
+
+    pub async fn handle_cancel(&self, client_id: u16) -> Result<CommandResult, Error> {
+        if !self.queue.lock().await.remove(client_id) {
+            return Err(Error::GameRuleError(
+                "You are not waiting for a match".to_string(),
+            ));
+        }
+        Ok(CommandResult::MatchmakingCancelled(client_id))
+    }
+
+    pub async fn remove_client(&self, client_id: u16) {
+        self.queue.lock().await.remove(client_id);
+    }
+
+    /// Starts every match that can be formed from the current queue.
+    pub async fn process_queue(&self) {
+        let matches = self.queue.lock().await.take_matches(Instant::now());
+        for group in matches {
+            if let Err(e) = self.start_match(group).await {
+                warn!("Failed to start match: {:?}", e);
+            }
+        }
+    }
+
+    /// Creates a room for the group, joins everyone into it and tells them where
+    /// to go. The first client to have queued hosts the room.
+    async fn start_match(&self, group: Vec<MatchRequest>) -> Result<(), Error> {
+        let Some(first) = group.first() else {
+            return Ok(());
+        };
+        let settings = RoomSettings {
+            max_players: group.iter().map(|r| r.players).max().unwrap_or(group.len()),
+            ..RoomSettings::default()
+        };
+
+        let config = GameConfig::for_mode(&first.mode, None).map_err(Error::GameRuleError)?;
+        let room_code = match self
+            .event_emitter
+            .emit_command(
+                Topic::RoomService,
+                Command::CreateRoom(first.mode.clone(), config, settings, Some(first.client_id)),
+            )
+            .await?
+        {
+            CommandResult::RoomCreated(room_code, _) => room_code,
+            result => {
+                return Err(Error::GameError(format!(
+                    "Unexpected result when creating a match room: {:?}",
+                    result
+                )))
+            }
+        };
+
+        for request in &group {
+            let result = self
+                .event_emitter
+                .emit_command(
+                    Topic::RoomService,
+                    Command::RequestPlayerJoin(
+                        request.client_id,
+                        JoinPayload {
+                            room_code: room_code.clone(),
+                            player_username: request.player_username.clone(),
+                            spectator: false,
+                            passphrase: None,
+                            invite_code: None,
+                        },
+                        None,
+                    ),
+                )
+                .await?;
+            if !matches!(result, CommandResult::PlayerJoined(_)) {
+                warn!(
+                    "Could not join client {} into match {}: {:?}",
+                    request.client_id, room_code, result
+                );
+                continue;
+            }
+
+            self.event_emitter
+                .emit_command(
+                    Topic::ClientService,
+                    Command::SendToClient(
+                        request.client_id,
+                        ServerMessage::MatchFound {
+                            room_code: room_code.clone(),
+                            players: group.len(),
+                        },
+                    ),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Periodically retries the queue so waiting clients fall back to a smaller
+    /// match once they time out.
+    pub fn spawn_matchmaker(&self) {
+        let matchmaking_service = self.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(MATCHMAKING_INTERVAL);
+            loop {
+                interval.tick().await;
+                matchmaking_service.process_queue().await;
+            }
+        });
+    }
+}
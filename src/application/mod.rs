@@ -1,3 +1,4 @@
 pub mod client;
 pub mod game;
+pub mod matchmaking;
 pub mod room;
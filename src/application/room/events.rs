@@ -1,26 +1,41 @@
 use crate::{
     domain::events::{AppEvent, Command, CommandResult, Event},
-    infra::event_emmiter::{EventEmitterError, EventListener},
+    infra::{
+        error::Error,
+        event_emmiter::{EventEmitterError, EventListener},
+    },
 };
 use thiserror::Error;
-use tracing::{error, info, warn};
+use tracing::{info, warn};
 
 use super::service::RoomService;
 
 #[derive(Error, Debug)]
 pub enum RoomServiceError {
-    #[error("Failed to handle join: {0}")]
-    JoinError(String),
-    #[error("Failed to handle player move: {0}")]
-    MoveError(String),
-    #[error("Failed to handle request cards: {0}")]
-    RequestCardsError(String),
-    #[error("Failed to create room: {0}")]
-    CreateRoomError(String),
-    #[error("Failed to broadcast game state: {0}")]
-    BroadcastError(String),
-    #[error("Failed to handle player leave: {0}")]
-    LeaveError(String),
+    #[error("{0}: {1:?}")]
+    JoinError(String, Error),
+    #[error("{0}: {1:?}")]
+    MoveError(String, Error),
+    #[error("{0}: {1:?}")]
+    RequestCardsError(String, Error),
+    #[error("{0}: {1:?}")]
+    CreateRoomError(String, Error),
+    #[error("{0}: {1:?}")]
+    BroadcastError(String, Error),
+    #[error("{0}: {1:?}")]
+    LeaveError(String, Error),
+    #[error("{0}: {1:?}")]
+    ResetError(String, Error),
+    #[error("{0}: {1:?}")]
+    StartError(String, Error),
+    #[error("{0}: {1:?}")]
+    HostActionError(String, Error),
+    #[error("{0}: {1:?}")]
+    ChatError(String, Error),
+    #[error("{0}: {1:?}")]
+    VoteError(String, Error),
+    #[error("{0}: {1:?}")]
+    HintError(String, Error),
     #[error("Failed to send command result: {0}")]
     SendResultError(String),
 }
@@ -31,41 +46,168 @@ impl From<RoomServiceError> for EventEmitterError {
     }
 }
 
+impl RoomServiceError {
+    /// The message for the client that sent the failed command.
+    fn client_message(&self) -> String {
+        match self {
+            RoomServiceError::JoinError(_, e)
+            | RoomServiceError::MoveError(_, e)
+            | RoomServiceError::RequestCardsError(_, e)
+            | RoomServiceError::CreateRoomError(_, e)
+            | RoomServiceError::BroadcastError(_, e)
+            | RoomServiceError::LeaveError(_, e)
+            | RoomServiceError::ResetError(_, e)
+            | RoomServiceError::StartError(_, e)
+            | RoomServiceError::HostActionError(_, e)
+            | RoomServiceError::ChatError(_, e)
+            | RoomServiceError::VoteError(_, e)
+            | RoomServiceError::HintError(_, e) => e.client_message(),
+            RoomServiceError::SendResultError(_) => "Something went wrong".to_string(),
+        }
+    }
+}
+
 impl RoomService {
     pub async fn handle_command(
         &self,
         command: Command,
     ) -> Result<CommandResult, RoomServiceError> {
         match command {
-            Command::CreateRoom(mode) => self.start_new_game(mode).await.map_err(|e| {
-                RoomServiceError::CreateRoomError(format!("Failed to create room: {:?}", e))
-            }),
-            Command::RequestPlayerJoin(client_id, message) => {
-                self.handle_join(message, client_id).await.map_err(|e| {
-                    RoomServiceError::JoinError(format!(
-                        "Failed to handle join for client {}: {:?}",
-                        client_id, e
-                    ))
+            Command::CreateRoom(mode, config, settings, host) => self
+                .start_new_game(mode, config, settings, host)
+                .await
+                .map_err(|e| {
+                    RoomServiceError::CreateRoomError("Failed to create room".to_string(), e)
+                }),
+            Command::RequestPlayerJoin(client_id, payload, peer) => self
+                .handle_join(payload, client_id, peer)
+                .await
+                .map_err(|e| {
+                    RoomServiceError::JoinError(
+                        format!("Failed to handle join for client {}", client_id),
+                        e,
+                    )
+                }),
+            Command::PlayerMove(client_id, game_move) => self
+                .handle_player_move(client_id, game_move)
+                .await
+                .map_err(|e| {
+                    RoomServiceError::MoveError(
+                        format!("Failed to handle move for client {}", client_id),
+                        e,
+                    )
+                }),
+            Command::RequestCards(client_id, room_code) => self
+                .handle_request_cards(client_id, room_code)
+                .await
+                .map_err(|e| {
+                    RoomServiceError::RequestCardsError(
+                        format!("Failed to handle request cards for client {}", client_id),
+                        e,
+                    )
+                }),
+            Command::RemovePlayerFromRoom(client_id, room_code) => self
+                .handle_leave_room(client_id, room_code)
+                .await
+                .map_err(|e| {
+                    RoomServiceError::LeaveError(
+                        format!("Failed to handle leave for client {}", client_id),
+                        e,
+                    )
+                }),
+            Command::ResetGame(client_id, room_code) => {
+                self.handle_reset(client_id, room_code).await.map_err(|e| {
+                    RoomServiceError::ResetError(
+                        format!("Failed to handle reset for client {}", client_id),
+                        e,
+                    )
                 })
             }
-            Command::PlayerMove(client_id, message) => self
-                .handle_player_move(client_id, message)
+            Command::SetPlayerReady(client_id, room_code, ready) => self
+                .handle_ready(client_id, room_code, ready)
                 .await
                 .map_err(|e| {
-                    RoomServiceError::MoveError(format!(
-                        "Failed to handle move for client {}: {:?}",
-                        client_id, e
-                    ))
+                    RoomServiceError::StartError(
+                        format!("Failed to set ready for client {}", client_id),
+                        e,
+                    )
                 }),
-            Command::RequestCards(client_id, message) => self
-                .handle_request_cards(client_id, message)
+            Command::StartGame(client_id, room_code) => {
+                self.handle_start(client_id, room_code).await.map_err(|e| {
+                    RoomServiceError::StartError(
+                        format!("Failed to start game for client {}", client_id),
+                        e,
+                    )
+                })
+            }
+            Command::KickPlayer(client_id, room_code, target) => self
+                .handle_kick(client_id, room_code, target)
                 .await
                 .map_err(|e| {
-                    RoomServiceError::RequestCardsError(format!(
-                        "Failed to handle request cards for client {}: {:?}",
-                        client_id, e
-                    ))
+                    RoomServiceError::HostActionError(
+                        format!("Failed to kick player {} for client {}", target, client_id),
+                        e,
+                    )
                 }),
+            Command::SetGameMode(client_id, room_code, mode, target_score) => self
+                .handle_set_mode(client_id, room_code, mode, target_score)
+                .await
+                .map_err(|e| {
+                    RoomServiceError::HostActionError(
+                        format!("Failed to change mode for client {}", client_id),
+                        e,
+                    )
+                }),
+            Command::LockRoom(client_id, room_code, locked) => self
+                .handle_lock(client_id, room_code, locked)
+                .await
+                .map_err(|e| {
+                    RoomServiceError::HostActionError(
+                        format!("Failed to lock room for client {}", client_id),
+                        e,
+                    )
+                }),
+            Command::SendChatMessage(client_id, room_code, message) => self
+                .handle_chat(client_id, room_code, message)
+                .await
+                .map_err(|e| {
+                    RoomServiceError::ChatError(
+                        format!("Failed to send chat message for client {}", client_id),
+                        e,
+                    )
+                }),
+            Command::MutePlayer(client_id, room_code, target, muted) => self
+                .handle_mute(client_id, room_code, target, muted)
+                .await
+                .map_err(|e| {
+                    RoomServiceError::ChatError(
+                        format!("Failed to mute player {} for client {}", target, client_id),
+                        e,
+                    )
+                }),
+            Command::CastVote(client_id, room_code, kind) => self
+                .handle_vote(client_id, room_code, kind)
+                .await
+                .map_err(|e| {
+                    RoomServiceError::VoteError(
+                        format!("Failed to record vote for client {}", client_id),
+                        e,
+                    )
+                }),
+            Command::RequestHint(client_id, room_code) => {
+                self.handle_hint(client_id, room_code).await.map_err(|e| {
+                    RoomServiceError::HintError(
+                        format!("Failed to give client {} a hint", client_id),
+                        e,
+                    )
+                })
+            }
+            Command::ListPublicRooms => {
+                Ok(CommandResult::PublicRooms(self.list_public_rooms().await))
+            }
+            Command::GetDailyLeaderboard(day) => Ok(CommandResult::DailyLeaderboard(
+                self.daily_leaderboard(day).await,
+            )),
             _ => Ok(CommandResult::NotHandled),
         }
     }
@@ -87,34 +229,57 @@ impl EventListener for RoomService {
 impl RoomService {
     async fn handle_event_occurred(&self, event: Event) -> Result<(), RoomServiceError> {
         match event {
-            Event::PlayerJoinedRoom(_client_id, ref room_code)
-            | Event::PlayerFoundSet(_client_id, ref room_code)
-            | Event::PlayerRequestedCards(_client_id, ref room_code)
-            | Event::PlayerLeft(_client_id, ref room_code)
-            | Event::GameOver(_client_id, ref room_code) => {
+            Event::PlayerJoinedRoom(_, ref room_code)
+            | Event::PlayerFoundSet(_, ref room_code)
+            | Event::PlayerPenalized(_, ref room_code)
+            | Event::PlayerRequestedCards(_, ref room_code)
+            | Event::PlayerLeft(_, ref room_code)
+            | Event::GameReset(_, ref room_code)
+            | Event::PlayerReady(_, ref room_code)
+            | Event::CountdownStarted(_, ref room_code)
+            | Event::GameStarted(_, ref room_code)
+            | Event::GameOver(_, ref room_code)
+            | Event::PlayerKicked(_, ref room_code)
+            | Event::RoomSettingsChanged(_, ref room_code)
+            | Event::HostChanged(_, ref room_code)
+            | Event::VoteUpdated(_, ref room_code)
+            | Event::VotePassed(_, ref room_code)
+            | Event::HintGiven(_, ref room_code)
+            | Event::VotesExpired(ref room_code) => {
                 self.broadcast_game_state(room_code.clone())
                     .await
                     .map_err(|e| {
-                        RoomServiceError::BroadcastError(format!(
-                            "Failed to broadcast game state for room {}: {:?}",
-                            room_code, e
-                        ))
+                        RoomServiceError::BroadcastError(
+                            format!("Failed to broadcast game state for room {}", room_code),
+                            e,
+                        )
                     })?;
                 info!(
                     "Broadcasted game state for room {} after event: {:?}",
                     room_code, event
                 );
-                Ok(())
+                if !event.changes_lobby() {
+                    return Ok(());
+                }
+                self.lobby_changed(room_code).await.map_err(|e| {
+                    RoomServiceError::BroadcastError("Failed to broadcast lobby".to_string(), e)
+                })
             }
+            Event::RoomCreated(ref room_code) => self.lobby_changed(room_code).await.map_err(|e| {
+                RoomServiceError::BroadcastError("Failed to broadcast lobby".to_string(), e)
+            }),
             Event::ClientRemoved(client_id, room_code) => {
                 if let Some(code) = room_code {
                     self.handle_leave(client_id, code.clone())
                         .await
                         .map_err(|e| {
-                            RoomServiceError::LeaveError(format!(
-                                "Failed to handle leave for client {} in room {}: {:?}",
-                                client_id, code, e
-                            ))
+                            RoomServiceError::LeaveError(
+                                format!(
+                                    "Failed to handle leave for client {} in room {}",
+                                    client_id, code
+                                ),
+                                e,
+                            )
                         })?;
                     info!("Handled leave for client {} in room {}", client_id, code);
                 }
@@ -132,11 +297,13 @@ impl RoomService {
         command: Command,
         result_sender: tokio::sync::mpsc::Sender<CommandResult>,
     ) -> Result<(), RoomServiceError> {
-        let result = self.handle_command(command).await?;
+        let result = self.handle_command(command).await.unwrap_or_else(|e| {
+            warn!("{}", e);
+            CommandResult::Error(e.client_message())
+        });
         result_sender.send(result).await.map_err(|e| {
             RoomServiceError::SendResultError(format!("Failed to send command result: {:?}", e))
         })?;
         Ok(())
     }
 }
-
@@ -1,24 +1,46 @@
-use std::sync::Arc;
+use std::{
+    net::IpAddr,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
 
 use ahash::{HashMap, HashMapExt};
 use async_trait::async_trait;
 use tokio::sync::Mutex;
+use tracing::{info, warn};
 
 use crate::{
     domain::{
         events::{Command, CommandResult, Event, Topic},
-        game::game::{Game, GameMode, Move},
-        message::WsMessage,
-        room::{Room, RoomServiceTrait},
+        game::{
+            config::{GameConfig, PenaltyPolicy},
+            game::{Game, GameMode, Move, MoveRejection},
+            player::PlayerId,
+        },
+        leaderboard::{DailyLeaderboard, DailyLeaderboardView},
+        message::{JoinPayload, ServerMessage},
+        room::{JoinRejection, Room, RoomServiceTrait, RoomSettings, RoomSummary},
+        vote::{VoteKind, VoteOutcome},
     },
     infra::{error::Error, event_emmiter::EventEmitter},
 };
 
 const ROOM_CODE_LENGTH: usize = 6;
+const INVITE_CODE_LENGTH: usize = 16;
+const START_COUNTDOWN: Duration = Duration::from_secs(3);
+const RECONNECT_SWEEP_INTERVAL: Duration = Duration::from_secs(30);
+const VOTE_SWEEP_INTERVAL: Duration = Duration::from_secs(5);
+/// How long room browser changes are collected before they are sent out.
+const LOBBY_BROADCAST_DELAY: Duration = Duration::from_millis(250);
 
 #[derive(Clone)]
 pub struct RoomService {
     rooms: Arc<Mutex<HashMap<String, Arc<Room>>>>,
+    daily: Arc<Mutex<DailyLeaderboard>>,
+    lobby_pending: Arc<AtomicBool>, // a room browser update is scheduled
     pub(super) event_emitter: Arc<EventEmitter>,
 }
 
@@ -26,20 +48,50 @@ impl RoomService {
     pub fn new(event_emitter: Arc<EventEmitter>) -> Self {
         Self {
             rooms: Arc::new(Mutex::new(HashMap::new())),
+            daily: Arc::new(Mutex::new(DailyLeaderboard::default())),
+            lobby_pending: Arc::new(AtomicBool::new(false)),
             event_emitter,
         }
     }
 
     pub async fn handle_join(
         &self,
-        message: WsMessage,
+        payload: JoinPayload,
         client_id: u16,
+        peer: Option<IpAddr>,
     ) -> Result<CommandResult, Error> {
-        let room_code = message.get_room_code()?;
-        let player_username = message.get_player_username()?;
-
-        let room = self.get_room(&room_code).await?;
-        room.join_player(client_id, player_username).await?;
+        let JoinPayload {
+            room_code,
+            player_username,
+            spectator,
+            passphrase,
+            invite_code,
+        } = payload;
+
+        let Ok(room) = self.get_room(&room_code).await else {
+            return Ok(CommandResult::JoinRejected(
+                room_code,
+                JoinRejection::RoomNotFound,
+            ));
+        };
+        if let Err(reason) = room
+            .check_access(
+                client_id,
+                peer,
+                passphrase.as_deref(),
+                invite_code.as_deref(),
+            )
+            .await
+        {
+            return Ok(CommandResult::JoinRejected(room_code, reason));
+        }
+        let rejoined = match room
+            .join_player(client_id, player_username.trim().to_string(), spectator)
+            .await
+        {
+            Ok(rejoined) => rejoined,
+            Err(reason) => return Ok(CommandResult::JoinRejected(room_code, reason)),
+        };
 
         self.event_emitter
             .emit_command(
@@ -48,22 +100,45 @@ impl RoomService {
             )
             .await?;
 
-        Ok(CommandResult::PlayerJoined(client_id))
+        let messages = room.chat_history().await;
+        if !messages.is_empty() {
+            self.event_emitter
+                .emit_command(
+                    Topic::ClientService,
+                    Command::SendToClient(
+                        client_id,
+                        ServerMessage::ChatHistory {
+                            room_code: room_code.clone(),
+                            messages,
+                        },
+                    ),
+                )
+                .await?;
+        }
+
+        if rejoined {
+            Ok(CommandResult::PlayerReJoined(client_id))
+        } else {
+            Ok(CommandResult::PlayerJoined(client_id))
+        }
     }
 
     pub async fn handle_player_move(
         &self,
         client_id: u16,
-        message: WsMessage,
+        game_move: Move,
     ) -> Result<CommandResult, Error> {
-        let game_move: Move = message.get_payload_as()?;
         let room_code = &game_move.room_code;
         let room = self.get_room(room_code).await?;
 
-        let move_successful = room.handle_move(client_id, &game_move.cards).await?;
-
-        if !move_successful {
-            return Ok(CommandResult::PlayerMoveInvalid);
+        if let Err(reason) = room.handle_move(client_id, &game_move.cards).await {
+            if reason == MoveRejection::NotASet && room.penalty().await != PenaltyPolicy::None {
+                self.event_emitter.emit_event(
+                    Topic::RoomService,
+                    Event::PlayerPenalized(client_id, room_code.clone()),
+                )?;
+            }
+            return Ok(CommandResult::PlayerMoveInvalid(reason));
         }
 
         self.event_emitter.emit_event(
@@ -72,6 +147,15 @@ impl RoomService {
         )?;
 
         if self.is_game_over(room_code).await? {
+            if let Some((day, result)) = room.daily_result(client_id).await {
+                let time_ms = result.time_ms;
+                if let Some(rank) = self.daily.lock().await.record(day, result) {
+                    info!(
+                        "Client {} solved the daily puzzle of day {} in {}ms, rank {}",
+                        client_id, day, time_ms, rank
+                    );
+                }
+            }
             self.event_emitter.emit_event(
                 Topic::RoomService,
                 Event::GameOver(client_id, room_code.clone()),
@@ -86,11 +170,6 @@ impl RoomService {
         room.is_game_over().await
     }
 
-    async fn get_room_game(&self, room_code: &str) -> Result<Arc<Mutex<Game>>, Error> {
-        let room = self.get_room(room_code).await?;
-        Ok(room.get_game_state().await)
-    }
-
     pub async fn get_room(&self, room_code: &str) -> Result<Arc<Room>, Error> {
         let rooms = self.rooms.lock().await;
         match rooms.get(room_code) {
@@ -102,10 +181,8 @@ impl RoomService {
     pub(super) async fn handle_request_cards(
         &self,
         client_id: u16,
-        message: WsMessage,
+        room_code: String,
     ) -> Result<CommandResult, Error> {
-        let room_code = message.get_room_code()?;
-
         let room = self.get_room(&room_code).await?;
         room.request_cards(client_id).await?;
 
@@ -133,33 +210,481 @@ impl RoomService {
         Ok(CommandResult::PlayerRemovedFromRoom(client_id, room_code))
     }
 
-    pub async fn start_new_game(&self, mode: GameMode) -> Result<CommandResult, Error> {
+    pub async fn handle_leave_room(
+        &self,
+        client_id: u16,
+        room_code: String,
+    ) -> Result<CommandResult, Error> {
+        let room = self.get_room(&room_code).await?;
+        room.leave_player(client_id).await?;
+
+        self.event_emitter
+            .emit_command(
+                Topic::ClientService,
+                Command::ClearClientRoomCode(client_id),
+            )
+            .await?;
+
+        self.event_emitter.emit_event(
+            Topic::RoomService,
+            Event::PlayerLeft(client_id, room_code.clone()),
+        )?;
+
+        Ok(CommandResult::PlayerRemovedFromRoom(client_id, room_code))
+    }
+
+    pub async fn handle_reset(
+        &self,
+        client_id: u16,
+        room_code: String,
+    ) -> Result<CommandResult, Error> {
+        let room = self.get_room(&room_code).await?;
+        room.reset_game(client_id).await?;
+
+        self.event_emitter.emit_event(
+            Topic::RoomService,
+            Event::GameReset(client_id, room_code.clone()),
+        )?;
+
+        Ok(CommandResult::GameReset(room_code))
+    }
+
+    pub async fn handle_ready(
+        &self,
+        client_id: u16,
+        room_code: String,
+        ready: bool,
+    ) -> Result<CommandResult, Error> {
+        let room = self.get_room(&room_code).await?;
+        let all_ready = room.set_player_ready(client_id, ready).await?;
+
+        self.event_emitter.emit_event(
+            Topic::RoomService,
+            Event::PlayerReady(client_id, room_code.clone()),
+        )?;
+
+        if all_ready {
+            self.start_countdown(client_id, room_code, room).await?;
+        }
+
+        Ok(CommandResult::PlayerReady(client_id))
+    }
+
+    pub async fn handle_start(
+        &self,
+        client_id: u16,
+        room_code: String,
+    ) -> Result<CommandResult, Error> {
+        let room = self.get_room(&room_code).await?;
+        room.ensure_host(client_id, "start the game").await?;
+
+        if !self
+            .start_countdown(client_id, room_code.clone(), room)
+            .await?
+        {
+            return Err(Error::GameRuleError(
+                "The game has already started".to_string(),
+            ));
+        }
+
+        Ok(CommandResult::CountdownStarted(room_code))
+    }
+
+    /// Starts the synchronized countdown and deals the board once it runs out.
+    /// Returns `false` if the room was not waiting for players.
+    async fn start_countdown(
+        &self,
+        client_id: u16,
+        room_code: String,
+        room: Arc<Room>,
+    ) -> Result<bool, Error> {
+        let Some(countdown_ends_at) = room.begin_countdown(START_COUNTDOWN).await else {
+            return Ok(false);
+        };
+
+        self.event_emitter.emit_event(
+            Topic::RoomService,
+            Event::CountdownStarted(client_id, room_code.clone()),
+        )?;
+
+        let event_emitter = self.event_emitter.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(START_COUNTDOWN).await;
+            if room.start_game(countdown_ends_at).await {
+                if let Err(e) = event_emitter.emit_event(
+                    Topic::RoomService,
+                    Event::GameStarted(client_id, room_code.clone()),
+                ) {
+                    warn!("Failed to start game in room {}: {:?}", room_code, e);
+                }
+            }
+        });
+
+        Ok(true)
+    }
+
+    pub async fn handle_kick(
+        &self,
+        client_id: u16,
+        room_code: String,
+        target: PlayerId,
+    ) -> Result<CommandResult, Error> {
+        let room = self.get_room(&room_code).await?;
+        let target_id = room.kick_player(client_id, target).await?;
+        self.notify_kicked(target_id, &room_code).await?;
+
+        self.event_emitter.emit_event(
+            Topic::RoomService,
+            Event::PlayerKicked(client_id, room_code),
+        )?;
+
+        Ok(CommandResult::PlayerKicked(target_id))
+    }
+
+    pub async fn handle_hint(
+        &self,
+        client_id: u16,
+        room_code: String,
+    ) -> Result<CommandResult, Error> {
+        let room = self.get_room(&room_code).await?;
+        let cards = room.hint(client_id).await?;
+
+        self.event_emitter
+            .emit_command(
+                Topic::ClientService,
+                Command::SendToClient(
+                    client_id,
+                    ServerMessage::Hint {
+                        room_code: room_code.clone(),
+                        cards,
+                    },
+                ),
+            )
+            .await?;
+
+        self.event_emitter
+            .emit_event(Topic::RoomService, Event::HintGiven(client_id, room_code))?;
+
+        Ok(CommandResult::HintGiven(client_id))
+    }
+
+    /// Detaches a kicked client from the room and tells them about it.
+    async fn notify_kicked(&self, target_id: u16, room_code: &str) -> Result<(), Error> {
+        self.event_emitter
+            .emit_command(
+                Topic::ClientService,
+                Command::ClearClientRoomCode(target_id),
+            )
+            .await?;
+
+        // The kicked client may already be gone, so failing to notify them is fine.
+        if let Err(e) = self
+            .event_emitter
+            .emit_command(
+                Topic::ClientService,
+                Command::SendToClient(
+                    target_id,
+                    ServerMessage::Kicked {
+                        room_code: room_code.to_string(),
+                    },
+                ),
+            )
+            .await
+        {
+            warn!("Failed to notify kicked client {}: {:?}", target_id, e);
+        }
+        Ok(())
+    }
+
+    pub async fn handle_vote(
+        &self,
+        client_id: u16,
+        room_code: String,
+        kind: VoteKind,
+    ) -> Result<CommandResult, Error> {
+        let room = self.get_room(&room_code).await?;
+        let outcome = room.vote(client_id, kind).await?;
+
+        let event = match &outcome {
+            VoteOutcome::Passed(kind) => {
+                if let VoteKind::Kick { player_id } = *kind {
+                    let target_id = room.client_of(player_id).await?;
+                    self.notify_kicked(target_id, &room_code).await?;
+                }
+                Event::VotePassed(client_id, room_code.clone())
+            }
+            VoteOutcome::Pending => Event::VoteUpdated(client_id, room_code.clone()),
+        };
+        self.event_emitter.emit_event(Topic::RoomService, event)?;
+
+        // Adding cards to an exhausted deck ends the game when no set is left.
+        if outcome == VoteOutcome::Passed(VoteKind::AddCards)
+            && self.is_game_over(&room_code).await?
+        {
+            self.event_emitter
+                .emit_event(Topic::RoomService, Event::GameOver(client_id, room_code))?;
+        }
+
+        Ok(CommandResult::VoteRecorded(client_id))
+    }
+
+    pub async fn handle_set_mode(
+        &self,
+        client_id: u16,
+        room_code: String,
+        mode: GameMode,
+        target_score: Option<i64>,
+    ) -> Result<CommandResult, Error> {
+        let room = self.get_room(&room_code).await?;
+        room.set_mode(client_id, mode, target_score).await?;
+
+        self.event_emitter.emit_event(
+            Topic::RoomService,
+            Event::RoomSettingsChanged(client_id, room_code.clone()),
+        )?;
+
+        Ok(CommandResult::RoomUpdated(room_code))
+    }
+
+    pub async fn handle_lock(
+        &self,
+        client_id: u16,
+        room_code: String,
+        locked: bool,
+    ) -> Result<CommandResult, Error> {
+        let room = self.get_room(&room_code).await?;
+        room.set_locked(client_id, locked).await?;
+
+        self.event_emitter.emit_event(
+            Topic::RoomService,
+            Event::RoomSettingsChanged(client_id, room_code.clone()),
+        )?;
+
+        Ok(CommandResult::RoomUpdated(room_code))
+    }
+
+    pub async fn handle_chat(
+        &self,
+        client_id: u16,
+        room_code: String,
+        message: String,
+    ) -> Result<CommandResult, Error> {
+        let room = self.get_room(&room_code).await?;
+        let chat_message = room
+            .post_chat_message(client_id, message.trim().to_string())
+            .await?;
+
+        self.event_emitter
+            .emit_command(
+                Topic::ClientService,
+                Command::BroadcastMessage(room_code, ServerMessage::Chat(chat_message)),
+            )
+            .await?;
+
+        Ok(CommandResult::ChatMessageSent)
+    }
+
+    pub async fn handle_mute(
+        &self,
+        client_id: u16,
+        room_code: String,
+        target: PlayerId,
+        muted: bool,
+    ) -> Result<CommandResult, Error> {
+        let room = self.get_room(&room_code).await?;
+        let target_id = room.set_muted(client_id, target, muted).await?;
+
+        let message = if muted {
+            "You have been muted by the host"
+        } else {
+            "The host has unmuted you"
+        };
+        if let Err(e) = self
+            .event_emitter
+            .emit_command(
+                Topic::ClientService,
+                Command::SendToClient(
+                    target_id,
+                    ServerMessage::Notice {
+                        message: message.to_string(),
+                    },
+                ),
+            )
+            .await
+        {
+            warn!("Failed to notify muted client {}: {:?}", target_id, e);
+        }
+
+        Ok(CommandResult::PlayerMuted(target_id))
+    }
+
+    /// Drops players whose reconnection window ran out in every room, handing the
+    /// host role on where the host was among them.
+    pub async fn expire_disconnected_players(&self) -> Result<(), Error> {
+        let rooms: Vec<(String, Arc<Room>)> = self
+            .rooms
+            .lock()
+            .await
+            .iter()
+            .map(|(room_code, room)| (room_code.clone(), room.clone()))
+            .collect();
+
+        for (room_code, room) in rooms {
+            if let Some(new_host) = room.expire_disconnected_players().await {
+                self.event_emitter
+                    .emit_event(Topic::RoomService, Event::HostChanged(new_host, room_code))?;
+            }
+        }
+        Ok(())
+    }
+
+    pub async fn expire_votes(&self) -> Result<(), Error> {
+        let rooms: Vec<(String, Arc<Room>)> = self
+            .rooms
+            .lock()
+            .await
+            .iter()
+            .map(|(room_code, room)| (room_code.clone(), room.clone()))
+            .collect();
+
+        for (room_code, room) in rooms {
+            if room.expire_votes().await {
+                self.event_emitter
+                    .emit_event(Topic::RoomService, Event::VotesExpired(room_code))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Periodically runs [`RoomService::expire_votes`].
+    pub fn spawn_vote_sweeper(&self) {
+        let room_service = self.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(VOTE_SWEEP_INTERVAL);
+            loop {
+                interval.tick().await;
+                if let Err(e) = room_service.expire_votes().await {
+                    warn!("Failed to expire votes: {:?}", e);
+                }
+            }
+        });
+    }
+
+    /// Periodically runs [`RoomService::expire_disconnected_players`].
+    pub fn spawn_reconnect_sweeper(&self) {
+        let room_service = self.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(RECONNECT_SWEEP_INTERVAL);
+            loop {
+                interval.tick().await;
+                if let Err(e) = room_service.expire_disconnected_players().await {
+                    warn!("Failed to expire disconnected players: {:?}", e);
+                }
+            }
+        });
+    }
+
+    pub async fn start_new_game(
+        &self,
+        mode: GameMode,
+        config: GameConfig,
+        settings: RoomSettings,
+        host: Option<u16>,
+    ) -> Result<CommandResult, Error> {
         let room_code = self.generate_room_code();
-        let game = Game::new(mode);
-        let room = Room::new(game);
+        let invite_code = settings
+            .invite_only
+            .then(|| self.generate_code(INVITE_CODE_LENGTH));
+        let public = settings.public;
+        let game = Game::new(mode, config);
+        info!("Created room {}", room_code);
+        let room = Room::new(game, settings, invite_code.clone(), host);
+
+        self.rooms
+            .lock()
+            .await
+            .insert(room_code.clone(), Arc::new(room));
+
+        if public {
+            self.event_emitter
+                .emit_event(Topic::RoomService, Event::RoomCreated(room_code.clone()))?;
+        }
 
-        let mut rooms = self.rooms.lock().await;
-        rooms.insert(room_code.clone(), Arc::new(room));
+        Ok(CommandResult::RoomCreated(room_code, invite_code))
+    }
 
-        Ok(CommandResult::RoomCreated(room_code))
+    pub async fn list_public_rooms(&self) -> Vec<RoomSummary> {
+        let rooms: Vec<(String, Arc<Room>)> = self
+            .rooms
+            .lock()
+            .await
+            .iter()
+            .map(|(room_code, room)| (room_code.clone(), room.clone()))
+            .collect();
+
+        let mut summaries = Vec::new();
+        for (room_code, room) in rooms {
+            if let Some(summary) = room.public_summary(&room_code).await {
+                summaries.push(summary);
+            }
+        }
+        summaries.sort_by_key(|summary| summary.age_secs);
+        summaries
+    }
+
+    pub async fn daily_leaderboard(&self, day: u64) -> DailyLeaderboardView {
+        self.daily.lock().await.view(day)
+    }
+
+    /// Schedules a room browser update after a change to a public room. Changes
+    /// made while an update is pending go out with that update.
+    pub async fn lobby_changed(&self, room_code: &str) -> Result<(), Error> {
+        let room = self.get_room(room_code).await?;
+        if !room.is_public() || self.lobby_pending.swap(true, Ordering::SeqCst) {
+            return Ok(());
+        }
+
+        let service = self.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(LOBBY_BROADCAST_DELAY).await;
+            service.lobby_pending.store(false, Ordering::SeqCst);
+            if let Err(e) = service.broadcast_lobby().await {
+                warn!("Failed to broadcast lobby: {:?}", e);
+            }
+        });
+        Ok(())
+    }
+
+    /// Pushes the current room browser listing to clients watching the lobby.
+    pub async fn broadcast_lobby(&self) -> Result<(), Error> {
+        let rooms = self.list_public_rooms().await;
+        self.event_emitter
+            .emit_command(Topic::ClientService, Command::BroadcastLobby(rooms))
+            .await?;
+        Ok(())
     }
 
     pub async fn broadcast_game_state(&self, room_code: String) -> Result<(), Error> {
-        let game_arc = self.get_room_game(&room_code).await?;
+        let room = self.get_room(&room_code).await?;
+        let game_view = room.get_game_view().await;
         self.event_emitter
             .emit_command(
                 Topic::ClientService,
-                Command::BroadcastGameState(room_code, game_arc),
+                Command::BroadcastGameState(room_code, Box::new(game_view)),
             )
             .await?;
         Ok(())
     }
 
     fn generate_room_code(&self) -> String {
+        self.generate_code(ROOM_CODE_LENGTH)
+    }
+
+    fn generate_code(&self, length: usize) -> String {
         use rand::{distributions::Alphanumeric, thread_rng, Rng};
         thread_rng()
             .sample_iter(&Alphanumeric)
-            .take(ROOM_CODE_LENGTH)
+            .take(length)
             .map(char::from)
             .collect()
     }
@@ -169,18 +694,19 @@ impl RoomService {
 impl RoomServiceTrait for RoomService {
     async fn handle_join(
         &self,
-        message: WsMessage,
+        payload: JoinPayload,
         client_id: u16,
+        peer: Option<IpAddr>,
     ) -> Result<CommandResult, Error> {
-        self.handle_join(message, client_id).await
+        self.handle_join(payload, client_id, peer).await
     }
 
     async fn handle_player_move(
         &self,
         client_id: u16,
-        message: WsMessage,
+        game_move: Move,
     ) -> Result<CommandResult, Error> {
-        self.handle_player_move(client_id, message).await
+        self.handle_player_move(client_id, game_move).await
     }
 
     async fn get_room(&self, room_code: &str) -> Result<Arc<Room>, Error> {
@@ -190,9 +716,9 @@ impl RoomServiceTrait for RoomService {
     async fn handle_request_cards(
         &self,
         client_id: u16,
-        message: WsMessage,
+        room_code: String,
     ) -> Result<CommandResult, Error> {
-        self.handle_request_cards(client_id, message).await
+        self.handle_request_cards(client_id, room_code).await
     }
 
     async fn handle_leave(
@@ -203,8 +729,116 @@ impl RoomServiceTrait for RoomService {
         self.handle_leave(client_id, room_code).await
     }
 
-    async fn start_new_game(&self, mode: GameMode) -> Result<CommandResult, Error> {
-        self.start_new_game(mode).await
+    async fn handle_leave_room(
+        &self,
+        client_id: u16,
+        room_code: String,
+    ) -> Result<CommandResult, Error> {
+        self.handle_leave_room(client_id, room_code).await
+    }
+
+    async fn handle_reset(
+        &self,
+        client_id: u16,
+        room_code: String,
+    ) -> Result<CommandResult, Error> {
+        self.handle_reset(client_id, room_code).await
+    }
+
+    async fn handle_ready(
+        &self,
+        client_id: u16,
+        room_code: String,
+        ready: bool,
+    ) -> Result<CommandResult, Error> {
+        self.handle_ready(client_id, room_code, ready).await
+    }
+
+    async fn handle_start(
+        &self,
+        client_id: u16,
+        room_code: String,
+    ) -> Result<CommandResult, Error> {
+        self.handle_start(client_id, room_code).await
+    }
+
+    async fn handle_kick(
+        &self,
+        client_id: u16,
+        room_code: String,
+        target: PlayerId,
+    ) -> Result<CommandResult, Error> {
+        self.handle_kick(client_id, room_code, target).await
+    }
+
+    async fn handle_set_mode(
+        &self,
+        client_id: u16,
+        room_code: String,
+        mode: GameMode,
+        target_score: Option<i64>,
+    ) -> Result<CommandResult, Error> {
+        self.handle_set_mode(client_id, room_code, mode, target_score)
+            .await
+    }
+
+    async fn handle_lock(
+        &self,
+        client_id: u16,
+        room_code: String,
+        locked: bool,
+    ) -> Result<CommandResult, Error> {
+        self.handle_lock(client_id, room_code, locked).await
+    }
+
+    async fn handle_chat(
+        &self,
+        client_id: u16,
+        room_code: String,
+        message: String,
+    ) -> Result<CommandResult, Error> {
+        self.handle_chat(client_id, room_code, message).await
+    }
+
+    async fn handle_mute(
+        &self,
+        client_id: u16,
+        room_code: String,
+        target: PlayerId,
+        muted: bool,
+    ) -> Result<CommandResult, Error> {
+        self.handle_mute(client_id, room_code, target, muted).await
+    }
+
+    async fn handle_vote(
+        &self,
+        client_id: u16,
+        room_code: String,
+        kind: VoteKind,
+    ) -> Result<CommandResult, Error> {
+        self.handle_vote(client_id, room_code, kind).await
+    }
+
+    async fn handle_hint(&self, client_id: u16, room_code: String) -> Result<CommandResult, Error> {
+        self.handle_hint(client_id, room_code).await
+    }
+
+    async fn start_new_game(
+        &self,
+        mode: GameMode,
+        config: GameConfig,
+        settings: RoomSettings,
+        host: Option<u16>,
+    ) -> Result<CommandResult, Error> {
+        self.start_new_game(mode, config, settings, host).await
+    }
+
+    async fn list_public_rooms(&self) -> Vec<RoomSummary> {
+        self.list_public_rooms().await
+    }
+
+    async fn daily_leaderboard(&self, day: u64) -> DailyLeaderboardView {
+        self.daily_leaderboard(day).await
     }
 
     async fn broadcast_game_state(&self, room_code: String) -> Result<(), Error> {
@@ -0,0 +1,157 @@
+use std::{
+    net::IpAddr,
+    time::{Duration, Instant},
+};
+
+use ahash::{HashMap, HashMapExt};
+
+/// Wrong guesses a peer may make before it has to wait between tries.
+const FREE_PEER_ATTEMPTS: u32 = 3;
+/// Wrong guesses from everyone together before the whole room slows down, so
+/// an attacker gains nothing by spreading guesses over many addresses.
+const FREE_ROOM_ATTEMPTS: u32 = 20;
+const FIRST_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(5 * 60);
+
+/// Wrong guesses from one source and when it may guess again.
+#[derive(Debug, Clone, Copy)]
+struct Strikes {
+    failures: u32,
+    retry_at: Instant,
+}
+
+impl Strikes {
+    /// Counts one more wrong guess. Once the free attempts are used up, every
+    /// further guess doubles the wait.
+    fn record(strikes: Option<Strikes>, free_attempts: u32, now: Instant) -> Strikes {
+        let failures = strikes.map_or(0, |s| s.failures) + 1;
+        let backoff = match failures.checked_sub(free_attempts) {
+            None => Duration::ZERO,
+            Some(extra) => FIRST_BACKOFF
+                .saturating_mul(2u32.saturating_pow(extra))
+                .min(MAX_BACKOFF),
+        };
+        Strikes {
+            failures,
+            retry_at: now + backoff,
+        }
+    }
+}
+
+/// Slows down wrong passphrase guesses in a room, per peer address and for the
+/// room as a whole. Joins whose address is unknown only count towards the room.
+#[derive(Debug)]
+pub struct PassphraseBackoff {
+    room: Option<Strikes>,
+    peers: HashMap<IpAddr, Strikes>,
+}
+
+impl Default for PassphraseBackoff {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PassphraseBackoff {
+    pub fn new() -> Self {
+        Self {
+            room: None,
+            peers: HashMap::new(),
+        }
+    }
+
+    /// Whether a guess from `peer` may be checked at `now`.
+    pub fn may_guess(&self, peer: Option<IpAddr>, now: Instant) -> bool {
+        let waiting = |strikes: Option<&Strikes>| strikes.is_some_and(|s| now < s.retry_at);
+        !waiting(self.room.as_ref()) && !waiting(peer.and_then(|peer| self.peers.get(&peer)))
+    }
+
+    pub fn record_failure(&mut self, peer: Option<IpAddr>, now: Instant) {
+        self.room = Some(Strikes::record(self.room, FREE_ROOM_ATTEMPTS, now));
+        if let Some(peer) = peer {
+            let strikes = Strikes::record(self.peers.get(&peer).copied(), FREE_PEER_ATTEMPTS, now);
+            self.peers.insert(peer, strikes);
+        }
+    }
+
+    /// Forgets the wrong guesses of a peer that got the passphrase right. The
+    /// room-wide count is kept, as the guesses may have come from someone else.
+    pub fn record_success(&mut self, peer: Option<IpAddr>) {
+        if let Some(peer) = peer {
+            self.peers.remove(&peer);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::Ipv4Addr;
+
+    use super::*;
+
+    fn peer(last: u8) -> Option<IpAddr> {
+        Some(IpAddr::V4(Ipv4Addr::new(10, 0, 0, last)))
+    }
+
+    #[test]
+    fn peers_get_free_attempts_before_backing_off() {
+        let mut backoff = PassphraseBackoff::new();
+        let now = Instant::now();
+        for _ in 0..FREE_PEER_ATTEMPTS - 1 {
+            backoff.record_failure(peer(1), now);
+            assert!(backoff.may_guess(peer(1), now));
+        }
+        backoff.record_failure(peer(1), now);
+        assert!(!backoff.may_guess(peer(1), now));
+        assert!(backoff.may_guess(peer(1), now + FIRST_BACKOFF));
+        assert!(backoff.may_guess(peer(2), now));
+    }
+
+    #[test]
+    fn backoff_doubles_up_to_the_maximum() {
+        let mut backoff = PassphraseBackoff::new();
+        let mut now = Instant::now();
+        for _ in 0..FREE_PEER_ATTEMPTS {
+            backoff.record_failure(peer(1), now);
+        }
+        let mut wait = FIRST_BACKOFF;
+        for _ in 0..16 {
+            assert!(!backoff.may_guess(peer(1), now + wait - Duration::from_millis(1)));
+            assert!(backoff.may_guess(peer(1), now + wait));
+            now += wait;
+            backoff.record_failure(peer(1), now);
+            wait = (wait * 2).min(MAX_BACKOFF);
+        }
+        assert_eq!(wait, MAX_BACKOFF);
+    }
+
+    #[test]
+    fn success_clears_the_peer_but_not_the_room() {
+        let mut backoff = PassphraseBackoff::new();
+        let now = Instant::now();
+        for last in 0..FREE_ROOM_ATTEMPTS - 1 {
+            backoff.record_failure(peer(last as u8), now);
+        }
+        for _ in 0..FREE_PEER_ATTEMPTS {
+            backoff.record_failure(peer(200), now);
+        }
+        backoff.record_success(peer(200));
+        assert!(!backoff.peers.contains_key(&peer(200).unwrap()));
+        assert!(!backoff.may_guess(peer(200), now));
+        assert!(!backoff.may_guess(None, now));
+    }
+
+    #[test]
+    fn guesses_spread_over_addresses_slow_down_the_room() {
+        let mut backoff = PassphraseBackoff::new();
+        let now = Instant::now();
+        for last in 0..FREE_ROOM_ATTEMPTS - 1 {
+            backoff.record_failure(peer(last as u8), now);
+            assert!(backoff.may_guess(peer(last as u8 + 1), now));
+        }
+        backoff.record_failure(None, now);
+        assert!(!backoff.may_guess(peer(250), now));
+        assert!(!backoff.may_guess(None, now));
+        assert!(backoff.may_guess(peer(250), now + FIRST_BACKOFF));
+    }
+}
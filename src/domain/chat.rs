@@ -0,0 +1,105 @@
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use ahash::{HashMap, HashMapExt, HashSet, HashSetExt};
+use serde::Serialize;
+
+use super::game::player::PlayerId;
+use crate::infra::error::Error;
+
+const CHAT_HISTORY_LIMIT: usize = 50;
+const CHAT_RATE_LIMIT: usize = 5; // messages per client per window
+const CHAT_RATE_WINDOW: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct ChatMessage {
+    pub player_id: PlayerId,
+    pub name: String,
+    pub message: String,
+    pub sent_at: u64, // milliseconds since the unix epoch
+}
+
+/// A room's chat: the recent history replayed to clients when they (re)join,
+/// plus per-client rate limiting and the host's mute list.
+#[derive(Debug)]
+pub struct ChatLog {
+    history: VecDeque<ChatMessage>,
+    recent: HashMap<u16, VecDeque<Instant>>,
+    muted: HashSet<u16>,
+}
+
+impl Default for ChatLog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ChatLog {
+    pub fn new() -> Self {
+        Self {
+            history: VecDeque::with_capacity(CHAT_HISTORY_LIMIT),
+            recent: HashMap::new(),
+            muted: HashSet::new(),
+        }
+    }
+
+    /// Records a message, dropping the oldest one once the history is full.
+    pub fn post(
+        &mut self,
+        client_id: u16,
+        player_id: PlayerId,
+        name: String,
+        message: String,
+    ) -> Result<ChatMessage, Error> {
+        if self.muted.contains(&client_id) {
+            return Err(Error::PermissionDenied(
+                "You have been muted by the host".to_string(),
+            ));
+        }
+
+        let now = Instant::now();
+        let recent = self.recent.entry(client_id).or_default();
+        while recent
+            .front()
+            .is_some_and(|sent| now.duration_since(*sent) >= CHAT_RATE_WINDOW)
+        {
+            recent.pop_front();
+        }
+        if recent.len() >= CHAT_RATE_LIMIT {
+            return Err(Error::RateLimited(
+                "You are sending messages too quickly".to_string(),
+            ));
+        }
+        recent.push_back(now);
+
+        let chat_message = ChatMessage {
+            player_id,
+            name,
+            message,
+            sent_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .expect("Time went backwards")
+                .as_millis() as u64,
+        };
+
+        if self.history.len() == CHAT_HISTORY_LIMIT {
+            self.history.pop_front();
+        }
+        self.history.push_back(chat_message.clone());
+        Ok(chat_message)
+    }
+
+    pub fn history(&self) -> Vec<ChatMessage> {
+        self.history.iter().cloned().collect()
+    }
+
+    pub fn set_muted(&mut self, client_id: u16, muted: bool) {
+        if muted {
+            self.muted.insert(client_id);
+        } else {
+            self.muted.remove(&client_id);
+        }
+    }
+}
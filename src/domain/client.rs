@@ -3,7 +3,9 @@ use std::sync::Arc;
 use async_trait::async_trait;
 use tokio::sync::{mpsc::UnboundedSender, Mutex};
 
-use super::{events::CommandResult, game::game::Game};
+use super::{
+    events::CommandResult, game::view::GameView, message::ServerMessage, room::RoomSummary,
+};
 use crate::infra::error::Error;
 
 #[derive(Debug, Eq, PartialEq)]
@@ -15,18 +17,20 @@ pub enum ClientState {
 #[derive(Debug)]
 pub struct Client {
     pub id: u16,
-    pub tx: UnboundedSender<Game>,
+    pub tx: UnboundedSender<ServerMessage>,
     state: ClientState,
     past_rooms: Vec<String>,
+    watching_lobby: bool, // receives room browser updates
 }
 
 impl Client {
-    pub fn new(tx: UnboundedSender<Game>, id: u16) -> Self {
+    pub fn new(tx: UnboundedSender<ServerMessage>, id: u16) -> Self {
         Self {
             id,
             tx,
             state: ClientState::Lobby,
             past_rooms: vec![],
+            watching_lobby: false,
         }
     }
 
@@ -37,9 +41,9 @@ impl Client {
         }
     }
 
-    pub async fn send_message(&self, game_state: &Game) -> Result<(), Error> {
-        self.tx.send(game_state.clone()).map_err(|err| {
-            Error::WebsocketError(format!("Failed to send game state to client: {:?}", err))
+    pub async fn send_message(&self, message: ServerMessage) -> Result<(), Error> {
+        self.tx.send(message).map_err(|err| {
+            Error::WebsocketError(format!("Failed to send message to client: {:?}", err))
         })
     }
 
@@ -50,6 +54,10 @@ impl Client {
         }
     }
 
+    pub fn leave_room(&mut self) {
+        self.state = ClientState::Lobby;
+    }
+
     pub fn get_past_rooms(&self) -> &Vec<String> {
         &self.past_rooms
     }
@@ -69,6 +77,14 @@ impl Client {
     pub fn remove_past_room(&mut self, room_code: &str) {
         self.past_rooms.retain(|room| room != room_code);
     }
+
+    pub fn set_watching_lobby(&mut self, watching: bool) {
+        self.watching_lobby = watching;
+    }
+
+    pub fn is_watching_lobby(&self) -> bool {
+        self.watching_lobby
+    }
 }
 
 #[async_trait]
@@ -78,14 +94,23 @@ pub trait ClientServiceTrait {
     async fn setup_or_update_client(
         &self,
         client_id: u16,
-        tx: UnboundedSender<Game>,
+        tx: UnboundedSender<ServerMessage>,
     ) -> Result<CommandResult, Error>;
     async fn remove_client(&self, id: u16) -> Result<(), Error>;
     async fn join_room(&self, client_id: u16, room_code: String) -> Result<CommandResult, Error>;
+    async fn leave_room(&self, client_id: u16) -> Result<CommandResult, Error>;
     async fn get_clients_in_room(&self, room_code: &str) -> Result<Vec<Arc<Mutex<Client>>>, Error>;
     async fn broadcast_game_state(
         &self,
         room_code: String,
-        game_state: Arc<Mutex<Game>>,
+        game_view: GameView,
+    ) -> Result<CommandResult, Error>;
+    async fn broadcast_message(
+        &self,
+        room_code: String,
+        message: ServerMessage,
     ) -> Result<CommandResult, Error>;
+    async fn send_to_client(&self, client_id: u16, message: ServerMessage) -> Result<(), Error>;
+    async fn watch_lobby(&self, client_id: u16, watching: bool) -> Result<CommandResult, Error>;
+    async fn broadcast_lobby(&self, rooms: Vec<RoomSummary>) -> Result<CommandResult, Error>;
 }
@@ -1,20 +1,26 @@
-use std::sync::Arc;
+use std::net::IpAddr;
 
 use strum::{Display, EnumString};
-use tokio::sync::{
-    mpsc::{Sender, UnboundedSender},
-    Mutex,
-};
+use tokio::sync::mpsc::{Sender, UnboundedSender};
 
 use super::{
-    game::game::{Game, GameMode},
-    message::WsMessage,
+    game::{
+        config::GameConfig,
+        game::{GameMode, Move, MoveRejection},
+        player::PlayerId,
+        view::GameView,
+    },
+    leaderboard::DailyLeaderboardView,
+    message::{JoinPayload, QuickMatchPayload, ServerMessage},
+    room::{JoinRejection, RoomSettings, RoomSummary},
+    vote::VoteKind,
 };
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, EnumString, Display)]
 pub enum Topic {
     RoomService,
     ClientService,
+    Matchmaking,
 }
 
 #[derive(Debug, Clone)]
@@ -27,39 +33,106 @@ pub enum Event {
     ClientRoomCodeSet(u16, String),     // client_id, room_code
     ClientDisconnected(u16),            // client_id
     ClientRemoved(u16, Option<String>), // client_id
-    ClientConnected(u16, UnboundedSender<Game>),
+    ClientConnected(u16, UnboundedSender<ServerMessage>),
     GameOver(u16, String),             // room_code
+    GameReset(u16, String),            // client_id, room_code
+    PlayerReady(u16, String),          // client_id, room_code
+    CountdownStarted(u16, String),     // client_id, room_code
+    GameStarted(u16, String),          // client_id, room_code
     PlayerRequestedCards(u16, String), // client_id, room_code
     PlayerFoundSet(u16, String),       // client_id, room_code
+    PlayerPenalized(u16, String),      // client_id, room_code
+    PlayerKicked(u16, String),         // client_id of the host, room_code
+    RoomSettingsChanged(u16, String),  // client_id of the host, room_code
+    HostChanged(u16, String),          // client_id of the new host, room_code
+    VoteUpdated(u16, String),          // client_id of the voter, room_code
+    VotePassed(u16, String),           // client_id of the deciding voter, room_code
+    VotesExpired(String),              // room_code
+    HintGiven(u16, String),            // client_id, room_code
+}
+
+impl Event {
+    /// Whether the event can change how a room shows up in the room browser.
+    pub fn changes_lobby(&self) -> bool {
+        matches!(
+            self,
+            Event::RoomCreated(_)
+                | Event::PlayerJoinedRoom(..)
+                | Event::PlayerLeft(..)
+                | Event::PlayerKicked(..)
+                | Event::GameReset(..)
+                | Event::CountdownStarted(..)
+                | Event::GameStarted(..)
+                | Event::GameOver(..)
+                | Event::RoomSettingsChanged(..)
+                | Event::VotePassed(..)
+        )
+    }
 }
 
 #[derive(Debug, Clone)]
 pub enum Command {
-    CreateRoom(GameMode),
-    RequestPlayerJoin(u16, WsMessage),
-    SetupClient(u16, Sender<Game>),
+    CreateRoom(GameMode, GameConfig, RoomSettings, Option<u16>), // mode, config, settings, client_id of the host
+    RequestPlayerJoin(u16, JoinPayload, Option<IpAddr>),         // client_id, payload, peer address
+    SetupClient(u16, Sender<ServerMessage>),
     DisconnectClient(u16),
-    BroadcastGameState(String, Arc<Mutex<Game>>), // room_code, Game
+    BroadcastGameState(String, Box<GameView>), // room_code, GameView
     SetClientRoomCode(u16, String),
-    PlayerMove(u16, WsMessage),
-    RequestCards(u16, WsMessage),
-    RemovePlayerFromRoom(u16, String), // client_id, room_code
+    ClearClientRoomCode(u16),
+    PlayerMove(u16, Move),
+    RequestCards(u16, String),                       // client_id, room_code
+    RemovePlayerFromRoom(u16, String),               // client_id, room_code
+    ResetGame(u16, String),                          // client_id, room_code
+    SetPlayerReady(u16, String, bool),               // client_id, room_code, ready
+    StartGame(u16, String),                          // client_id, room_code
+    KickPlayer(u16, String, PlayerId),               // client_id, room_code, player to kick
+    SetGameMode(u16, String, GameMode, Option<i64>), // client_id, room_code, mode, target_score
+    LockRoom(u16, String, bool),                     // client_id, room_code, locked
+    CastVote(u16, String, VoteKind),                 // client_id, room_code, vote
+    RequestHint(u16, String),                        // client_id, room_code
+    SendToClient(u16, ServerMessage),
+    BroadcastMessage(String, ServerMessage), // room_code, message
+    SendChatMessage(u16, String, String),    // client_id, room_code, message
+    MutePlayer(u16, String, PlayerId, bool), // client_id, room_code, player to mute, muted
+    ListPublicRooms,
+    GetDailyLeaderboard(u64), // day
+    WatchLobby(u16, bool),    // client_id, watching
+    BroadcastLobby(Vec<RoomSummary>),
+    JoinMatchmaking(u16, QuickMatchPayload),
+    LeaveMatchmaking(u16),
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum CommandResult {
-    RoomCreated(String),
+    RoomCreated(String, Option<String>), // room_code, invite_code
     PlayerJoined(u16),
     PlayerReJoined(u16),
+    JoinRejected(String, JoinRejection), // room_code, reason
     ClientSetup(String),
     BroadcastDone(String),
     ClientRoomCodeSet(u16, String), // client_id, room_code
+    ClientRoomCodeCleared(u16),
     NotHandled,
     Error(String),
-    PlayerMoveInvalid,
+    PlayerMoveInvalid(MoveRejection),
     PlayerMoveValid,
     CardsRequested,
     PlayerRemovedFromRoom(u16, String), // client_id, room_code
+    GameReset(String),                  // room_code
+    PlayerReady(u16),
+    CountdownStarted(String), // room_code
+    PlayerKicked(u16),
+    RoomUpdated(String), // room_code
+    MessageSent(u16),
+    ChatMessageSent,
+    PlayerMuted(u16),
+    PublicRooms(Vec<RoomSummary>),
+    LobbyWatchUpdated(u16, bool), // client_id, watching
+    MatchmakingQueued(u16),
+    MatchmakingCancelled(u16),
+    VoteRecorded(u16),
+    HintGiven(u16),
+    DailyLeaderboard(DailyLeaderboardView),
 }
 
 #[derive(Debug, Clone)]
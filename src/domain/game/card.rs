@@ -1,6 +1,18 @@
+use std::fmt;
+
 use rand::Rng;
+use schemars::{
+    gen::SchemaGenerator,
+    schema::{InstanceType, Metadata, Schema, SchemaObject, SubschemaValidation},
+    JsonSchema,
+};
 use serde::{de, Deserialize, Deserializer, Serialize};
 
+use super::{
+    compact::{CompactCard, CARD_COUNT},
+    proset::{ProsetCard, PROSET_CARD_COUNT},
+};
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Shape {
     Diamond = 0,
@@ -17,6 +29,16 @@ impl Serialize for Shape {
     }
 }
 
+impl JsonSchema for Shape {
+    fn schema_name() -> String {
+        "Shape".to_string()
+    }
+
+    fn json_schema(_gen: &mut SchemaGenerator) -> Schema {
+        attribute_schema("0 = diamond, 1 = oval, 2 = squiggle")
+    }
+}
+
 impl<'de> Deserialize<'de> for Shape {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
@@ -48,6 +70,16 @@ impl Serialize for Color {
     }
 }
 
+impl JsonSchema for Color {
+    fn schema_name() -> String {
+        "Color".to_string()
+    }
+
+    fn json_schema(_gen: &mut SchemaGenerator) -> Schema {
+        attribute_schema("0 = red, 1 = purple, 2 = green")
+    }
+}
+
 impl<'de> Deserialize<'de> for Color {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
@@ -79,6 +111,16 @@ impl Serialize for Number {
     }
 }
 
+impl JsonSchema for Number {
+    fn schema_name() -> String {
+        "Number".to_string()
+    }
+
+    fn json_schema(_gen: &mut SchemaGenerator) -> Schema {
+        attribute_schema("0 = one, 1 = two, 2 = three")
+    }
+}
+
 impl<'de> Deserialize<'de> for Number {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
@@ -110,6 +152,16 @@ impl Serialize for Shading {
     }
 }
 
+impl JsonSchema for Shading {
+    fn schema_name() -> String {
+        "Shading".to_string()
+    }
+
+    fn json_schema(_gen: &mut SchemaGenerator) -> Schema {
+        attribute_schema("0 = outlined, 1 = striped, 2 = solid")
+    }
+}
+
 impl<'de> Deserialize<'de> for Shading {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
@@ -125,57 +177,159 @@ impl<'de> Deserialize<'de> for Shading {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
-pub struct Card {
+/// Card attributes travel as their integer index.
+fn attribute_schema(description: &str) -> Schema {
+    SchemaObject {
+        instance_type: Some(InstanceType::Integer.into()),
+        enum_values: Some(vec![0.into(), 1.into(), 2.into()]),
+        metadata: Some(Box::new(Metadata {
+            description: Some(description.to_string()),
+            ..Default::default()
+        })),
+        ..Default::default()
+    }
+    .into()
+}
+
+/// A card of the classic SET deck.
+#[derive(Debug, Clone, Copy, Serialize, JsonSchema, PartialEq, Eq, Hash)]
+pub struct ClassicCard {
     pub shape: Shape,
     pub color: Color,
     pub number: Number,
     pub shading: Shading,
 }
 
-impl Default for Card {
-    fn default() -> Self {
-        Self::new()
-    }
-}
-
-impl Card {
-    pub fn new() -> Self {
-        let mut rng = rand::thread_rng();
-
-        let shape = match rng.gen_range(0..3) {
-            0 => Shape::Diamond,
-            1 => Shape::Oval,
-            2 => Shape::Squiggle,
-            _ => unreachable!(),
-        };
-
-        let color = match rng.gen_range(0..3) {
-            0 => Color::Red,
-            1 => Color::Purple,
-            2 => Color::Green,
-            _ => unreachable!(),
-        };
-
-        let number = match rng.gen_range(0..3) {
-            0 => Number::One,
-            1 => Number::Two,
-            2 => Number::Three,
-            _ => unreachable!(),
-        };
-
-        let shading = match rng.gen_range(0..3) {
-            0 => Shading::Outlined,
-            1 => Shading::Striped,
-            2 => Shading::Solid,
-            _ => unreachable!(),
-        };
-
-        Self {
-            shape,
-            color,
-            number,
-            shading,
+impl ClassicCard {
+    /// A card picked uniformly at random by `rng`.
+    pub fn random(rng: &mut impl Rng) -> Self {
+        Self::from(CompactCard(rng.gen_range(0..CARD_COUNT)))
+    }
+}
+
+/// A card of any of the card families the game is played with. Which family a
+/// game deals is up to its [`Rules`](super::rules::Rules).
+#[derive(Debug, Clone, Serialize, PartialEq, Eq, Hash)]
+#[serde(untagged)]
+pub enum Card {
+    Classic(ClassicCard),
+    Proset(ProsetCard),
+}
+
+impl From<ClassicCard> for Card {
+    fn from(card: ClassicCard) -> Self {
+        Card::Classic(card)
+    }
+}
+
+impl From<ProsetCard> for Card {
+    fn from(card: ProsetCard) -> Self {
+        Card::Proset(card)
+    }
+}
+
+/// Every field a card object may have. Each field is checked on its own, so
+/// errors point at the offending field.
+#[derive(Deserialize)]
+struct CardFields {
+    shape: Option<Shape>,
+    color: Option<Color>,
+    number: Option<Number>,
+    shading: Option<Shading>,
+    #[serde(default, deserialize_with = "deserialize_dots")]
+    dots: Option<ProsetCard>,
+}
+
+fn deserialize_dots<'de, D>(deserializer: D) -> Result<Option<ProsetCard>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let dots: u8 = Deserialize::deserialize(deserializer)?;
+    ProsetCard::new(dots).map(Some).ok_or_else(|| {
+        de::Error::custom(format!("dots must be between 1 and {}", PROSET_CARD_COUNT))
+    })
+}
+
+/// Cards are accepted as an object of attributes, as a [`CompactCard`] index
+/// or, for ProSET, as an object of dots.
+impl<'de> Deserialize<'de> for Card {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct CardVisitor;
+
+        impl<'de> de::Visitor<'de> for CardVisitor {
+            type Value = Card;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(f, "a card object or a card index below {}", CARD_COUNT)
+            }
+
+            fn visit_u64<E: de::Error>(self, index: u64) -> Result<Card, E> {
+                u8::try_from(index)
+                    .ok()
+                    .and_then(CompactCard::new)
+                    .map(Card::from)
+                    .ok_or_else(|| E::custom(format!("Card index must be below {}", CARD_COUNT)))
+            }
+
+            fn visit_i64<E: de::Error>(self, index: i64) -> Result<Card, E> {
+                let index = u64::try_from(index)
+                    .map_err(|_| E::custom("Card index must not be negative"))?;
+                self.visit_u64(index)
+            }
+
+            fn visit_map<A: de::MapAccess<'de>>(self, map: A) -> Result<Card, A::Error> {
+                let fields = CardFields::deserialize(de::value::MapAccessDeserializer::new(map))?;
+                match fields {
+                    CardFields {
+                        shape: Some(shape),
+                        color: Some(color),
+                        number: Some(number),
+                        shading: Some(shading),
+                        dots: None,
+                    } => Ok(Card::Classic(ClassicCard {
+                        shape,
+                        color,
+                        number,
+                        shading,
+                    })),
+                    CardFields {
+                        shape: None,
+                        color: None,
+                        number: None,
+                        shading: None,
+                        dots: Some(card),
+                    } => Ok(Card::Proset(card)),
+                    _ => Err(de::Error::custom(
+                        "a card needs either shape, color, number and shading, or dots",
+                    )),
+                }
+            }
+        }
+
+        deserializer.deserialize_any(CardVisitor)
+    }
+}
+
+impl JsonSchema for Card {
+    fn schema_name() -> String {
+        "Card".to_string()
+    }
+
+    fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+        SchemaObject {
+            subschemas: Some(Box::new(SubschemaValidation {
+                any_of: Some(vec![
+                    gen.subschema_for::<ClassicCard>(),
+                    gen.subschema_for::<CompactCard>(),
+                    gen.subschema_for::<ProsetCard>(),
+                ]),
+                ..Default::default()
+            })),
+            ..Default::default()
         }
+        .into()
     }
 }
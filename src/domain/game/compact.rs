@@ -0,0 +1,243 @@
+use schemars::{
+    gen::SchemaGenerator,
+    schema::{InstanceType, Metadata, NumberValidation, Schema, SchemaObject},
+    JsonSchema,
+};
+use serde::{Deserialize, Serialize};
+
+use super::card::{Card, ClassicCard, Color, Number, Shading, Shape};
+
+pub const CARD_COUNT: u8 = 81;
+
+/// The third card of every pair, indexed by the pair's compact indices.
+static THIRD_CARDS: [[u8; CARD_COUNT as usize]; CARD_COUNT as usize] = third_card_table();
+
+const fn third_card_table() -> [[u8; CARD_COUNT as usize]; CARD_COUNT as usize] {
+    let mut table = [[0; CARD_COUNT as usize]; CARD_COUNT as usize];
+    let mut a = 0;
+    while a < CARD_COUNT {
+        let mut b = 0;
+        while b < CARD_COUNT {
+            let mut third = 0;
+            let mut place = 27;
+            while place > 0 {
+                let digit_a = a / place % 3;
+                let digit_b = b / place % 3;
+                third += (6 - digit_a - digit_b) % 3 * place;
+                place /= 3;
+            }
+            table[a as usize][b as usize] = third;
+            b += 1;
+        }
+        a += 1;
+    }
+    table
+}
+
+/// A card as a single index in `0..81`. Its base-3 digits, most significant
+/// first, are the shape, color, number and shading indices, so three cards form
+/// a set exactly when their digits sum to a multiple of three.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(try_from = "u8", into = "u8")]
+pub struct CompactCard(pub(super) u8);
+
+impl CompactCard {
+    pub fn new(index: u8) -> Option<Self> {
+        (index < CARD_COUNT).then_some(Self(index))
+    }
+
+    pub fn index(self) -> u8 {
+        self.0
+    }
+
+    /// The index of a classic card; cards of other families have none.
+    pub fn of(card: &Card) -> Option<Self> {
+        match card {
+            Card::Classic(card) => Some(card.into()),
+            Card::Proset(_) => None,
+        }
+    }
+
+    /// The one card that forms a set with `self` and `other`.
+    pub fn third(self, other: Self) -> Self {
+        Self(THIRD_CARDS[self.0 as usize][other.0 as usize])
+    }
+
+    /// Whether the three distinct cards form a set.
+    pub fn is_set(a: Self, b: Self, c: Self) -> bool {
+        a != b && a.third(b) == c
+    }
+}
+
+impl TryFrom<u8> for CompactCard {
+    type Error = String;
+
+    fn try_from(index: u8) -> Result<Self, Self::Error> {
+        Self::new(index).ok_or_else(|| format!("Card index must be below {}", CARD_COUNT))
+    }
+}
+
+impl From<CompactCard> for u8 {
+    fn from(card: CompactCard) -> Self {
+        card.0
+    }
+}
+
+impl From<&ClassicCard> for CompactCard {
+    fn from(card: &ClassicCard) -> Self {
+        Self(
+            card.shape as u8 * 27
+                + card.color as u8 * 9
+                + card.number as u8 * 3
+                + card.shading as u8,
+        )
+    }
+}
+
+impl From<CompactCard> for ClassicCard {
+    fn from(card: CompactCard) -> Self {
+        let digit = |place: u8| card.0 / place % 3;
+        ClassicCard {
+            shape: match digit(27) {
+                0 => Shape::Diamond,
+                1 => Shape::Oval,
+                _ => Shape::Squiggle,
+            },
+            color: match digit(9) {
+                0 => Color::Red,
+                1 => Color::Purple,
+                _ => Color::Green,
+            },
+            number: match digit(3) {
+                0 => Number::One,
+                1 => Number::Two,
+                _ => Number::Three,
+            },
+            shading: match digit(1) {
+                0 => Shading::Outlined,
+                1 => Shading::Striped,
+                _ => Shading::Solid,
+            },
+        }
+    }
+}
+
+impl From<CompactCard> for Card {
+    fn from(card: CompactCard) -> Self {
+        Card::Classic(card.into())
+    }
+}
+
+impl JsonSchema for CompactCard {
+    fn schema_name() -> String {
+        "CompactCard".to_string()
+    }
+
+    fn json_schema(_gen: &mut SchemaGenerator) -> Schema {
+        SchemaObject {
+            instance_type: Some(InstanceType::Integer.into()),
+            number: Some(Box::new(NumberValidation {
+                minimum: Some(0.0),
+                maximum: Some((CARD_COUNT - 1) as f64),
+                ..Default::default()
+            })),
+            metadata: Some(Box::new(Metadata {
+                description: Some("shape * 27 + color * 9 + number * 3 + shading".to_string()),
+                ..Default::default()
+            })),
+            ..Default::default()
+        }
+        .into()
+    }
+}
+
+/// How cards are written in messages sent to a client.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CardEncoding {
+    #[default]
+    Full, // an object with one field per attribute
+    Compact, // a single `CompactCard` index
+}
+
+/// A card as written for a client that asked for [`CardEncoding::Compact`].
+/// Only classic cards have an index; cards of other families stay objects.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(untagged)]
+pub enum EncodedCard {
+    Compact(CompactCard),
+    Full(Card),
+}
+
+impl From<Card> for EncodedCard {
+    fn from(card: Card) -> Self {
+        match CompactCard::of(&card) {
+            Some(card) => EncodedCard::Compact(card),
+            None => EncodedCard::Full(card),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::game::proset::ProsetCard;
+
+    fn attributes(card: CompactCard) -> [u8; 4] {
+        let card = ClassicCard::from(card);
+        [
+            card.shape as u8,
+            card.color as u8,
+            card.number as u8,
+            card.shading as u8,
+        ]
+    }
+
+    #[test]
+    fn indices_round_trip_through_classic_cards() {
+        for index in 0..CARD_COUNT {
+            let card = CompactCard(index);
+            assert_eq!(CompactCard::from(&ClassicCard::from(card)), card);
+        }
+        assert_eq!(CompactCard::new(CARD_COUNT), None);
+    }
+
+    #[test]
+    fn third_cards_complete_every_attribute() {
+        for a in 0..CARD_COUNT {
+            for b in 0..CARD_COUNT {
+                let (a, b) = (CompactCard(a), CompactCard(b));
+                let third = a.third(b);
+                assert_eq!(third, b.third(a));
+                for ((x, y), z) in attributes(a)
+                    .into_iter()
+                    .zip(attributes(b))
+                    .zip(attributes(third))
+                {
+                    assert_eq!((x + y + z) % 3, 0, "{:?} {:?} {:?}", a, b, third);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn a_card_is_no_set_with_itself() {
+        let card = CompactCard(40);
+        assert_eq!(card.third(card), card);
+        assert!(!CompactCard::is_set(card, card, card));
+    }
+
+    #[test]
+    fn only_classic_cards_are_encoded_as_indices() {
+        let classic = Card::from(CompactCard(40));
+        let encoded = serde_json::to_value(EncodedCard::from(classic.clone())).unwrap();
+        assert_eq!(encoded, serde_json::json!(40));
+        assert_eq!(serde_json::from_value::<Card>(encoded).unwrap(), classic);
+
+        let proset = Card::Proset(ProsetCard::new(5).unwrap());
+        assert_eq!(
+            serde_json::to_value(EncodedCard::from(proset.clone())).unwrap(),
+            serde_json::to_value(proset).unwrap()
+        );
+    }
+}
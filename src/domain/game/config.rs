@@ -0,0 +1,142 @@
+use serde::Serialize;
+
+use super::game::GameMode;
+
+const BEST_OF_3_SCORE: i64 = 3;
+const DEFAULT_RACE_TARGET: i64 = 5;
+pub const MAX_TARGET_SCORE: i64 = 27; // a deck holds at most 27 disjoint sets
+const DEFAULT_PENALTY_POINTS: i64 = 1;
+const MAX_PENALTY_POINTS: i64 = 10;
+pub const DEFAULT_LOCKOUT_SECS: u64 = 5;
+const MAX_LOCKOUT_SECS: u64 = 60;
+const DEFAULT_HINT_LIMIT: u32 = 3;
+const MAX_HINT_LIMIT: u32 = 20;
+const DEFAULT_HINT_COST: i64 = 1;
+const MAX_HINT_COST: i64 = 5;
+
+/// What happens to a player who calls a triple that is not a set.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(tag = "type", content = "value", rename_all = "snake_case")]
+pub enum PenaltyPolicy {
+    #[default]
+    None,
+    Deduction(i64), // points taken off the player's score, or a lockout if they have fewer
+    Lockout(u64),   // seconds before the player may call another set
+}
+
+impl PenaltyPolicy {
+    /// Parses the penalty options accepted by `/api/new`.
+    pub fn parse(
+        kind: Option<&str>,
+        points: Option<i64>,
+        lockout_secs: Option<u64>,
+    ) -> Result<Self, String> {
+        match kind.unwrap_or("none") {
+            "none" => Ok(PenaltyPolicy::None),
+            "deduction" => {
+                let points = points.unwrap_or(DEFAULT_PENALTY_POINTS);
+                if !(1..=MAX_PENALTY_POINTS).contains(&points) {
+                    return Err(format!(
+                        "penalty_points must be between 1 and {}",
+                        MAX_PENALTY_POINTS
+                    ));
+                }
+                Ok(PenaltyPolicy::Deduction(points))
+            }
+            "lockout" => {
+                let lockout_secs = lockout_secs.unwrap_or(DEFAULT_LOCKOUT_SECS);
+                if !(1..=MAX_LOCKOUT_SECS).contains(&lockout_secs) {
+                    return Err(format!(
+                        "lockout_secs must be between 1 and {}",
+                        MAX_LOCKOUT_SECS
+                    ));
+                }
+                Ok(PenaltyPolicy::Lockout(lockout_secs))
+            }
+            _ => Err("penalty must be one of none, deduction or lockout".to_string()),
+        }
+    }
+}
+
+/// Whether players may ask for hints, and what a hint costs them.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(tag = "type", content = "value", rename_all = "snake_case")]
+pub enum HintPolicy {
+    #[default]
+    Off,
+    Limited(u32), // hints per player per game
+    Cost(i64),    // points taken off the player's score for each hint, which they must have
+}
+
+impl HintPolicy {
+    /// Parses the hint options accepted by `/api/new`.
+    pub fn parse(
+        kind: Option<&str>,
+        limit: Option<u32>,
+        cost: Option<i64>,
+    ) -> Result<Self, String> {
+        match kind.unwrap_or("off") {
+            "off" => Ok(HintPolicy::Off),
+            "limited" => {
+                let limit = limit.unwrap_or(DEFAULT_HINT_LIMIT);
+                if !(1..=MAX_HINT_LIMIT).contains(&limit) {
+                    return Err(format!(
+                        "hint_limit must be between 1 and {}",
+                        MAX_HINT_LIMIT
+                    ));
+                }
+                Ok(HintPolicy::Limited(limit))
+            }
+            "cost" => {
+                let cost = cost.unwrap_or(DEFAULT_HINT_COST);
+                if !(1..=MAX_HINT_COST).contains(&cost) {
+                    return Err(format!("hint_cost must be between 1 and {}", MAX_HINT_COST));
+                }
+                Ok(HintPolicy::Cost(cost))
+            }
+            _ => Err("hints must be one of off, limited or cost".to_string()),
+        }
+    }
+}
+
+/// Per-room game rules, picked when the room is created.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct GameConfig {
+    /// The game ends as soon as a player reaches this score. Without a target it
+    /// runs until the deck is exhausted and no sets are left on the board.
+    pub target_score: Option<i64>,
+    pub penalty: PenaltyPolicy,
+    /// Deal three extra cards whenever the board has no set instead of waiting
+    /// for every player to request them.
+    pub auto_deal: bool,
+    pub hints: HintPolicy,
+    /// Seeds every shuffle of the game, so the same seed deals the same cards.
+    /// A random seed is picked when none is given.
+    pub seed: Option<u64>,
+}
+
+impl GameConfig {
+    /// Builds the config for a mode. Only race mode takes a custom target; a
+    /// daily game ends once every set on its board has been found, and ultra,
+    /// junior and ProSET games, like classic ones, run until the deck is
+    /// exhausted.
+    pub fn for_mode(mode: &GameMode, target_score: Option<i64>) -> Result<Self, String> {
+        let target_score = match (mode, target_score) {
+            (GameMode::BestOf3, None) => Some(BEST_OF_3_SCORE),
+            (GameMode::Race, target_score) => {
+                let target_score = target_score.unwrap_or(DEFAULT_RACE_TARGET);
+                if !(1..=MAX_TARGET_SCORE).contains(&target_score) {
+                    return Err(format!("target must be between 1 and {}", MAX_TARGET_SCORE));
+                }
+                Some(target_score)
+            }
+            (_, None) => None,
+            (_, Some(_)) => return Err("target is only supported in race mode".to_string()),
+        };
+
+        Ok(Self {
+            target_score,
+            ..Self::default()
+        })
+    }
+}
@@ -0,0 +1,78 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+use super::{card::Card, deck::Deck, solver};
+
+pub const DAILY_BOARD_SIZE: usize = 12;
+pub const DAILY_SETS: usize = 6; // sets hidden on every daily board
+const SECS_PER_DAY: u64 = 24 * 60 * 60;
+/// Keeps daily boards apart from the games dealt by `/api/new?seed=`.
+const DAILY_SEED_SALT: u64 = 0x5e7d_a117_0000_0000;
+
+/// The board of one day's puzzle: twelve cards hiding exactly [`DAILY_SETS`]
+/// sets. Found sets stay on the board; the puzzle is solved once every set has
+/// been found.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DailyPuzzle {
+    pub day: u64, // days since the unix epoch, in UTC
+    pub seed: u64,
+    pub board: Vec<Card>,
+    pub sets: usize,
+}
+
+impl DailyPuzzle {
+    /// Deals the puzzle for `day`. Every server deals the same board for the
+    /// same day.
+    pub fn for_day(day: u64) -> Self {
+        let seed = day ^ DAILY_SEED_SALT;
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        loop {
+            let mut deck = Deck::new();
+            deck.shuffle(&mut rng);
+            deck.cards.truncate(DAILY_BOARD_SIZE);
+            if solver::count_sets(&deck.cards) == DAILY_SETS {
+                return Self {
+                    day,
+                    seed,
+                    board: deck.cards,
+                    sets: DAILY_SETS,
+                };
+            }
+        }
+    }
+
+    pub fn today() -> Self {
+        Self::for_day(today())
+    }
+}
+
+/// The current day, counted in days since the unix epoch in UTC.
+pub fn today() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("Time went backwards")
+        .as_secs()
+        / SECS_PER_DAY
+}
+
+/// Formats a day as `YYYY-MM-DD`.
+pub fn date(day: u64) -> String {
+    // Howard Hinnant's civil_from_days, shifted so that years start in March.
+    let days = day as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day_of_month = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!("{:04}-{:02}-{:02}", year, month, day_of_month)
+}
@@ -1,8 +1,12 @@
-use serde::Serialize;
+use rand::{seq::SliceRandom, Rng};
 
-use super::card::{Card, Color, Number, Shading, Shape};
+use super::{
+    card::Card,
+    game::GameMode,
+    rules::{self, Classic, Rules},
+};
 
-#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Deck {
     pub cards: Vec<Card>,
 }
@@ -14,29 +18,24 @@ impl Default for Deck {
 }
 
 impl Deck {
+    /// The classic 81-card deck.
     pub fn new() -> Self {
-        let mut cards = Vec::new();
-        for &shape in &[Shape::Diamond, Shape::Oval, Shape::Squiggle] {
-            for &color in &[Color::Red, Color::Purple, Color::Green] {
-                for &number in &[Number::One, Number::Two, Number::Three] {
-                    for &shading in &[Shading::Outlined, Shading::Striped, Shading::Solid] {
-                        cards.push(Card {
-                            shape,
-                            color,
-                            number,
-                            shading,
-                        });
-                    }
-                }
-            }
+        Self::for_rules(&Classic)
+    }
+
+    /// The deck a game of `mode` is played with.
+    pub fn for_mode(mode: &GameMode) -> Self {
+        Self::for_rules(rules::for_mode(mode))
+    }
+
+    pub fn for_rules(rules: &dyn Rules) -> Self {
+        Self {
+            cards: rules.cards(),
         }
-        Self { cards }
     }
 
-    pub fn shuffle(&mut self) {
-        use rand::seq::SliceRandom;
-        let mut rng = rand::thread_rng();
-        self.cards.shuffle(&mut rng);
+    pub fn shuffle(&mut self, rng: &mut impl Rng) {
+        self.cards.shuffle(rng);
     }
 
     pub fn draw(&mut self) -> Option<Card> {
@@ -1,44 +1,90 @@
 use std::{
     fmt,
     str::FromStr,
-    time::{SystemTime, UNIX_EPOCH},
+    time::{Duration, SystemTime, UNIX_EPOCH},
 };
 
 use ahash::{HashMap, HashMapExt};
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use super::card::Card;
+use super::{
+    card::Card,
+    config::{GameConfig, HintPolicy, PenaltyPolicy, DEFAULT_LOCKOUT_SECS},
+    daily::DailyPuzzle,
+    rules::{self, Rules},
+    solver,
+};
 use crate::{
     domain::game::{deck::Deck, player::Player},
     infra::error::Error,
 };
 
-const BEST_OF_3_SCORE: i64 = 3;
+const RECONNECT_WINDOW_SECS: u64 = 5 * 60;
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum GameMode {
     Classic,
     BestOf3,
+    Race,
+    Daily,
+    Ultra,
+    Junior,
+    Proset,
 }
 
 #[derive(Debug, Clone, Serialize, PartialEq, Eq)]
 pub enum EventType {
     PlayerJoined,
+    PlayerLeft,
     PlayerFoundSet,
     PlayerMove,
     PlayerRequestedCards,
+    PlayerReady,
+    CountdownStarted,
+    GameStarted,
     GameOver,
+    GameReset,
+    HostChanged,
+    PlayerKicked,
+    ModeChanged,
+    PlayerPenalized,
+    CardsDealt,
+    DeckReshuffled,
+    VoteCast,
+    VotePassed,
+    VoteExpired,
+    HintUsed,
+    PuzzleSolved,
 }
 
 impl fmt::Display for EventType {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let string_representation = match self {
             EventType::PlayerJoined => "PlayerJoined",
+            EventType::PlayerLeft => "PlayerLeft",
             EventType::PlayerFoundSet => "PlayerFoundSet",
             EventType::PlayerRequestedCards => "PlayerRequestedCards",
             EventType::PlayerMove => "PlayerMove",
+            EventType::PlayerReady => "PlayerReady",
+            EventType::CountdownStarted => "CountdownStarted",
+            EventType::GameStarted => "GameStarted",
             EventType::GameOver => "GameOver",
+            EventType::GameReset => "GameReset",
+            EventType::HostChanged => "HostChanged",
+            EventType::PlayerKicked => "PlayerKicked",
+            EventType::ModeChanged => "ModeChanged",
+            EventType::PlayerPenalized => "PlayerPenalized",
+            EventType::CardsDealt => "CardsDealt",
+            EventType::DeckReshuffled => "DeckReshuffled",
+            EventType::VoteCast => "VoteCast",
+            EventType::VotePassed => "VotePassed",
+            EventType::VoteExpired => "VoteExpired",
+            EventType::HintUsed => "HintUsed",
+            EventType::PuzzleSolved => "PuzzleSolved",
         };
         write!(f, "{}", string_representation)
     }
@@ -61,6 +107,35 @@ impl Event {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MoveRejection {
+    WrongCardCount,
+    DuplicateCards,
+    CardNotInPlay,
+    NotASet,
+    GameNotInProgress,
+    NotAPlayer,
+    LockedOut,
+    AlreadyFound,
+}
+
+impl fmt::Display for MoveRejection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let string_representation = match self {
+            MoveRejection::WrongCardCount => "That many cards can't form a set in this mode",
+            MoveRejection::DuplicateCards => "The same card was selected more than once",
+            MoveRejection::CardNotInPlay => "Selected cards must be on the board",
+            MoveRejection::NotASet => "The selected cards do not form a set",
+            MoveRejection::GameNotInProgress => "The game has not started yet",
+            MoveRejection::NotAPlayer => "Only players can make moves",
+            MoveRejection::LockedOut => "You are locked out after a wrong call",
+            MoveRejection::AlreadyFound => "You already found this set",
+        };
+        write!(f, "{}", string_representation)
+    }
+}
+
 impl FromStr for GameMode {
     type Err = &'static str;
 
@@ -68,6 +143,11 @@ impl FromStr for GameMode {
         match s {
             "classic" => Ok(GameMode::Classic),
             "bestof3" => Ok(GameMode::BestOf3),
+            "race" => Ok(GameMode::Race),
+            "daily" => Ok(GameMode::Daily),
+            "ultra" => Ok(GameMode::Ultra),
+            "junior" => Ok(GameMode::Junior),
+            "proset" => Ok(GameMode::Proset),
             _ => Err("Invalid game mode"),
         }
     }
@@ -78,14 +158,20 @@ impl fmt::Display for GameMode {
         let string_representation = match self {
             GameMode::Classic => "classic",
             GameMode::BestOf3 => "bestof3",
+            GameMode::Race => "race",
+            GameMode::Daily => "daily",
+            GameMode::Ultra => "ultra",
+            GameMode::Junior => "junior",
+            GameMode::Proset => "proset",
         };
         write!(f, "{}", string_representation)
     }
 }
 
-#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[derive(Debug, Clone)]
 pub struct Game {
     pub deck: Deck,                  // The deck of cards
+    pub discard: Vec<Card>,          // Sets that have been found, in the order they were found
     pub game_over: Option<bool>,     // Indicates whether the game is over
     pub in_play: Vec<Card>,          // The cards currently in play, organized in rows
     pub last_player: Option<String>, // The last player who made a move
@@ -93,28 +179,47 @@ pub struct Game {
     pub players: Vec<Player>,        // The players in the game
     pub remaining: i64,              // The number of remaining cards in the deck
     pub state: GameState,
+    pub countdown_ends_at: Option<u64>, // Milliseconds since the unix epoch
     pub mode: GameMode,
+    pub config: GameConfig,
     pub disconnected_players: HashMap<u16, (u64, Player)>,
     pub events: Vec<Event>,
+    pub hints: HashMap<u16, (Vec<Card>, usize)>, // client_id -> hinted set, cards revealed so far
+    pub seed: u64, // Replaying a game with this seed deals the same cards
+    pub puzzle: Option<DailyPuzzle>, // The fixed board of a daily game
+    pub found: Vec<Vec<Card>>, // Sets found on a daily board, which stay in play
+    pub started_at: Option<u64>, // Milliseconds since the unix epoch
+    pub ended_at: Option<u64>, // Milliseconds since the unix epoch
+    rng: ChaCha8Rng,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub enum GameState {
     WaitingForPlayers,
+    Starting,
     InProgress,
     Ended,
 }
 
 impl Default for Game {
     fn default() -> Self {
-        Self::new(GameMode::Classic)
+        Self::new(GameMode::Classic, GameConfig::default())
     }
 }
 
 impl Game {
-    pub fn new(mode: GameMode) -> Self {
+    /// Creates a game whose shuffles all come from `config.seed`, so games with
+    /// the same seed and the same moves play out identically. Daily games deal
+    /// today's puzzle instead.
+    pub fn new(mode: GameMode, config: GameConfig) -> Self {
+        let puzzle = (mode == GameMode::Daily).then(DailyPuzzle::today);
+        let seed = match &puzzle {
+            Some(puzzle) => puzzle.seed,
+            None => config.seed.unwrap_or_else(rand::random),
+        };
         let mut game = Game {
-            deck: Deck::new(),
+            deck: Deck::for_mode(&mode),
+            discard: vec![],
             game_over: None,
             in_play: vec![],
             last_player: None,
@@ -122,15 +227,88 @@ impl Game {
             players: vec![],
             remaining: 0,
             state: GameState::WaitingForPlayers,
+            countdown_ends_at: None,
             mode,
+            config,
             disconnected_players: HashMap::new(),
             events: vec![],
+            hints: HashMap::new(),
+            seed,
+            puzzle,
+            found: vec![],
+            started_at: None,
+            ended_at: None,
+            rng: ChaCha8Rng::seed_from_u64(seed),
         };
-        game.deck.shuffle();
-        game.deal();
+        game.deck.shuffle(&mut game.rng);
+        game.remaining = game.deck.cards.len() as i64;
         game
     }
 
+    /// Marks a player as (not) ready. Returns `true` when every player in the
+    /// lobby is ready.
+    pub fn set_ready(&mut self, client_id: u16, ready: bool) -> Result<bool, Error> {
+        if self.state != GameState::WaitingForPlayers {
+            return Err(Error::GameRuleError(
+                "The game has already started".to_string(),
+            ));
+        }
+
+        let player = self
+            .players
+            .iter_mut()
+            .find(|p| p.client_id == client_id)
+            .ok_or_else(|| Error::PlayerNotFound(client_id.to_string()))?;
+        player.ready = ready;
+
+        if ready {
+            let player_name = player.name.clone();
+            self.events
+                .push(Event::new(EventType::PlayerReady, player_name));
+        }
+
+        Ok(self.players.iter().all(|p| p.ready))
+    }
+
+    /// Moves the lobby into the countdown before the game starts and returns when
+    /// it ends, or `None` if the game is not waiting for players.
+    pub fn begin_countdown(&mut self, countdown: Duration) -> Option<u64> {
+        if self.state != GameState::WaitingForPlayers || self.players.is_empty() {
+            return None;
+        }
+
+        let ends_at = SystemTime::now() + countdown;
+        let ends_at = ends_at
+            .duration_since(UNIX_EPOCH)
+            .expect("Time went backwards")
+            .as_millis() as u64;
+        self.countdown_ends_at = Some(ends_at);
+        self.state = GameState::Starting;
+        self.events.push(Event::new(
+            EventType::CountdownStarted,
+            countdown.as_secs().to_string(),
+        ));
+        Some(ends_at)
+    }
+
+    /// Deals the board and opens the game for moves once the countdown ending at
+    /// `countdown_ends_at` is over. A countdown cut short by a reset does not start
+    /// the game, even if another countdown is running by the time it ends.
+    pub fn start(&mut self, countdown_ends_at: u64) -> bool {
+        if self.state != GameState::Starting || self.countdown_ends_at != Some(countdown_ends_at) {
+            return false;
+        }
+
+        self.deal();
+        self.countdown_ends_at = None;
+        self.started_at = Some(now_millis());
+        self.state = GameState::InProgress;
+        self.events
+            .push(Event::new(EventType::GameStarted, self.mode.to_string()));
+        self.auto_deal();
+        true
+    }
+
     pub fn add_player(&mut self, player: Player) {
         self.events
             .push(Event::new(EventType::PlayerJoined, player.name.clone()));
@@ -154,6 +332,66 @@ impl Game {
         }
     }
 
+    /// Removes a player who left on purpose. Unlike [`Game::remove_player`], the
+    /// player is not kept around for a reconnect.
+    pub fn leave_player(&mut self, client_id: u16) -> bool {
+        let was_disconnected = self.disconnected_players.remove(&client_id).is_some();
+
+        if let Some(index) = self.players.iter().position(|p| p.client_id == client_id) {
+            let player = self.players.remove(index);
+            self.events
+                .push(Event::new(EventType::PlayerLeft, player.name));
+            true
+        } else {
+            was_disconnected
+        }
+    }
+
+    /// Drops disconnected players whose reconnection window has run out. Returns
+    /// `true` if anyone was dropped.
+    pub fn purge_expired_players(&mut self) -> bool {
+        let current_time = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("Time went backwards")
+            .as_secs();
+
+        let before = self.disconnected_players.len();
+        self.disconnected_players
+            .retain(|_, (timestamp, _)| current_time - *timestamp < RECONNECT_WINDOW_SECS);
+        self.disconnected_players.len() != before
+    }
+
+    /// Whether the client is seated in the game or still inside its reconnection
+    /// window.
+    pub fn has_player(&self, client_id: u16) -> bool {
+        self.players.iter().any(|p| p.client_id == client_id)
+            || self.disconnected_players.contains_key(&client_id)
+    }
+
+    pub fn set_mode(&mut self, mode: GameMode, config: GameConfig) -> Result<(), Error> {
+        if self.state != GameState::WaitingForPlayers {
+            return Err(Error::GameRuleError(
+                "The mode can only be changed before the game starts".to_string(),
+            ));
+        }
+        if mode == GameMode::Daily || self.mode == GameMode::Daily {
+            return Err(Error::GameRuleError(
+                "The daily puzzle is only played in rooms created for it".to_string(),
+            ));
+        }
+
+        self.events
+            .push(Event::new(EventType::ModeChanged, mode.to_string()));
+        if Deck::for_mode(&mode) != Deck::for_mode(&self.mode) {
+            self.deck = Deck::for_mode(&mode);
+            self.deck.shuffle(&mut self.rng);
+            self.remaining = self.deck.cards.len() as i64;
+        }
+        self.mode = mode;
+        self.config = config;
+        Ok(())
+    }
+
     pub fn restore_player(&mut self, client_id: u16) -> Result<(), &'static str> {
         if let Some((timestamp, player)) = self.disconnected_players.remove(&client_id) {
             let current_time = SystemTime::now()
@@ -161,20 +399,26 @@ impl Game {
                 .expect("Time went backwards")
                 .as_secs();
 
-            if current_time - timestamp < 5 * 60 {
+            if current_time - timestamp < RECONNECT_WINDOW_SECS {
                 self.players.push(player);
                 return Ok(());
-            } else {
-                // TODO: Remove player from game
             }
         }
         Err("Could not restore player")
     }
 
     pub fn deal(&mut self) {
-        let mut in_play = Vec::with_capacity(6);
+        if let Some(puzzle) = &self.puzzle {
+            self.in_play = puzzle.board.clone();
+            self.deck.cards.clear();
+            self.remaining = 0;
+            return;
+        }
+
+        let board_size = self.rules().board_size();
+        let mut in_play = Vec::with_capacity(board_size);
         if !self.deck.cards.is_empty() {
-            for _ in 0..12 {
+            for _ in 0..board_size {
                 if let Some(card) = self.deck.draw() {
                     in_play.push(card);
                 }
@@ -185,30 +429,60 @@ impl Game {
         self.remaining = self.deck.cards.len() as i64;
     }
 
-    pub fn make_move(&mut self, player_id: u16, selected_cards: &[Card]) -> Result<bool, Error> {
-        let (valid, err) = self.check_set(selected_cards);
-
-        if !valid || err.is_some() {
-            return Ok(false);
+    pub fn make_move(
+        &mut self,
+        player_id: u16,
+        selected_cards: &[Card],
+    ) -> Result<(), MoveRejection> {
+        if self.state != GameState::InProgress {
+            return Err(MoveRejection::GameNotInProgress);
         }
 
-        let indices: Vec<usize> = selected_cards
-            .iter()
-            .filter_map(|v| self.find_index(v))
-            .collect();
+        let now = now_millis();
+        let Some(player) = self.players.iter().find(|p| p.client_id == player_id) else {
+            return Err(MoveRejection::NotAPlayer);
+        };
+        if player.locked_until.is_some_and(|until| now < until) {
+            return Err(MoveRejection::LockedOut);
+        }
 
-        let mut indices = indices;
-        indices.sort();
-        indices.reverse();
+        if !self.rules().accepts(selected_cards.len()) {
+            return Err(MoveRejection::WrongCardCount);
+        }
 
-        for index in indices {
-            if let Some(card) = self.deck.draw() {
-                self.in_play.splice(index..index + 1, std::iter::once(card));
-            } else {
-                self.in_play.remove(index);
+        let mut indices = Vec::with_capacity(selected_cards.len());
+        for card in selected_cards {
+            let index = self.find_index(card).ok_or(MoveRejection::CardNotInPlay)?;
+            if indices.contains(&index) {
+                return Err(MoveRejection::DuplicateCards);
             }
+            indices.push(index);
         }
 
+        if !self.rules().is_set(selected_cards) {
+            self.penalize(player_id, now);
+            return Err(MoveRejection::NotASet);
+        }
+
+        if self.puzzle.is_some() {
+            if self.is_found(selected_cards) {
+                return Err(MoveRejection::AlreadyFound);
+            }
+            self.found.push(selected_cards.to_owned());
+        } else {
+            indices.sort();
+            indices.reverse();
+
+            for index in indices {
+                if let Some(card) = self.deck.draw() {
+                    self.in_play.splice(index..index + 1, std::iter::once(card));
+                } else {
+                    self.in_play.remove(index);
+                }
+            }
+
+            self.discard.extend_from_slice(selected_cards);
+        }
         self.update_score(player_id, 1);
 
         self.last_player = self
@@ -227,113 +501,482 @@ impl Game {
 
         self.remaining = self.deck.cards.len() as i64;
 
-        if self.mode == GameMode::Classic {
-            if self.deck.cards.is_empty() && self.check_remaining_sets() {
-                self.state = GameState::Ended;
-                self.game_over = Some(true);
-            }
-        } else if self.mode == GameMode::BestOf3
-            && self.players.iter().any(|p| p.score >= BEST_OF_3_SCORE)
-        {
-            self.state = GameState::Ended;
-            self.game_over = Some(true);
+        let target_reached = match (&self.puzzle, self.config.target_score) {
+            (Some(puzzle), _) => self.found.len() >= puzzle.sets,
+            (None, Some(target_score)) => self.players.iter().any(|p| p.score >= target_score),
+            (None, None) => false,
+        };
+        if target_reached {
+            self.finish(now);
+        } else {
+            self.auto_deal();
+            self.end_if_exhausted();
         }
 
-        Ok(true)
+        Ok(())
     }
 
-    pub fn check_set(&self, cards: &[Card]) -> (bool, Option<Error>) {
-        if cards.len() != 3 {
-            return (
-                false,
-                Some(Error::GameError("Sets must contain 3 cards".to_string())),
-            );
+    /// Ends the game once the deck is empty and the board has no set left, which
+    /// can happen before anyone reaches the target score. Auto-dealing games get
+    /// to reshuffle the found sets back in first. Returns `true` if the game
+    /// ended.
+    pub fn end_if_exhausted(&mut self) -> bool {
+        if self.state != GameState::InProgress
+            || !self.deck.cards.is_empty()
+            || !self.check_remaining_sets()
+        {
+            return false;
         }
 
-        let mut colors = HashMap::new();
-        let mut shapes = HashMap::new();
-        let mut numbers = HashMap::new();
-        let mut shadings = HashMap::new();
+        self.finish(now_millis());
+        true
+    }
 
-        for card in cards {
-            *colors.entry(card.color).or_insert(0) += 1;
-            *shapes.entry(card.shape).or_insert(0) += 1;
-            *numbers.entry(card.number).or_insert(0) += 1;
-            *shadings.entry(card.shading).or_insert(0) += 1;
-        }
+    fn finish(&mut self, now: u64) {
+        self.state = GameState::Ended;
+        self.game_over = Some(true);
+        self.ended_at = Some(now);
 
-        if colors.values().any(|&v| v == 2)
-            || shapes.values().any(|&v| v == 2)
-            || numbers.values().any(|&v| v == 2)
-            || shadings.values().any(|&v| v == 2)
-        {
-            return (false, None);
+        if let Some(time_ms) = self.solve_time() {
+            self.events.push(Event::new(
+                EventType::PuzzleSolved,
+                format!("Solved in {}.{:03}s", time_ms / 1000, time_ms % 1000),
+            ));
+            return;
         }
 
-        (true, None)
+        let Some(top_score) = self.players.iter().map(|p| p.score).max() else {
+            return;
+        };
+        let leaders: Vec<&str> = self
+            .players
+            .iter()
+            .filter(|p| p.score == top_score)
+            .map(|p| p.name.as_str())
+            .collect();
+        let data = match leaders.as_slice() {
+            [winner] => format!("Player {} wins with {} point(s)", winner, top_score),
+            _ => format!(
+                "Tie between {} with {} point(s)",
+                leaders.join(", "),
+                top_score
+            ),
+        };
+        self.events.push(Event::new(EventType::GameOver, data));
+    }
+
+    /// The rules of the game's card family.
+    pub fn rules(&self) -> &'static dyn Rules {
+        rules::for_mode(&self.mode)
     }
 
+    /// Returns `true` if there is no set left on the board.
     pub fn check_remaining_sets(&self) -> bool {
-        for i in 0..self.in_play.len() {
-            for j in (i + 1)..self.in_play.len() {
-                for k in (j + 1)..self.in_play.len() {
-                    if self
-                        .check_set(&[
-                            self.in_play[i].clone(),
-                            self.in_play[j].clone(),
-                            self.in_play[k].clone(),
-                        ])
-                        .0
-                    {
-                        return false;
-                    }
-                }
+        self.rules().first_set(&self.in_play).is_none()
+    }
+
+    /// The first set on the board that has not been found yet, if there is one.
+    pub fn find_set(&self) -> Option<Vec<Card>> {
+        let cards =
+            |set: &[usize]| -> Vec<Card> { set.iter().map(|&i| self.in_play[i].clone()).collect() };
+
+        if self.found.is_empty() {
+            return self.rules().first_set(&self.in_play).map(|set| cards(&set));
+        }
+
+        // Only daily boards keep their found sets, and those are classic.
+        solver::find_sets(&self.in_play)
+            .into_iter()
+            .map(|set| cards(&set))
+            .find(|set| !self.is_found(set))
+    }
+
+    /// Whether the cards were already found on a daily board, in any order.
+    pub fn is_found(&self, cards: &[Card]) -> bool {
+        self.found
+            .iter()
+            .any(|set| cards.iter().all(|card| set.contains(card)))
+    }
+
+    /// Milliseconds from the start of a solved daily puzzle to its last set.
+    pub fn solve_time(&self) -> Option<u64> {
+        self.puzzle.as_ref()?;
+        Some(self.ended_at?.saturating_sub(self.started_at?))
+    }
+
+    /// How many sets there are among the cards on the board.
+    pub fn sets_on_board(&self) -> usize {
+        self.rules().count_sets(&self.in_play)
+    }
+
+    /// Reveals one card of a set on the board to the player, then a second card
+    /// of the same set on the next hint. A new set is picked once the hinted one
+    /// is no longer on the board.
+    pub fn hint(&mut self, player_id: u16) -> Result<Vec<Card>, Error> {
+        if self.state != GameState::InProgress {
+            return Err(Error::GameRuleError(
+                "The game has not started yet".to_string(),
+            ));
+        }
+
+        let player = self
+            .players
+            .iter()
+            .find(|p| p.client_id == player_id)
+            .ok_or_else(|| Error::PlayerNotFound(player_id.to_string()))?;
+        match self.config.hints {
+            HintPolicy::Off => {
+                return Err(Error::GameRuleError(
+                    "Hints are turned off in this room".to_string(),
+                ))
+            }
+            HintPolicy::Limited(limit) if player.hints_used >= limit => {
+                return Err(Error::GameRuleError(format!(
+                    "You have used all {} of your hints",
+                    limit
+                )))
             }
+            HintPolicy::Cost(cost) if player.score < cost => {
+                return Err(Error::GameRuleError(format!(
+                    "A hint costs {} point(s) and you have {}",
+                    cost, player.score
+                )))
+            }
+            HintPolicy::Limited(_) | HintPolicy::Cost(_) => {}
         }
 
-        true
+        let current = self
+            .hints
+            .get(&player_id)
+            .filter(|(set, _)| {
+                set.iter().all(|card| self.in_play.contains(card)) && !self.is_found(set)
+            })
+            .cloned();
+        let (set, revealed) = match current {
+            Some((_, revealed)) if revealed >= 2 => {
+                return Err(Error::GameRuleError(
+                    "You already know two cards of this set".to_string(),
+                ))
+            }
+            Some((set, revealed)) => (set, revealed + 1),
+            None => {
+                let set = self.find_set().ok_or_else(|| {
+                    Error::GameRuleError("There is no set on the board".to_string())
+                })?;
+                (set, 1)
+            }
+        };
+        self.hints.insert(player_id, (set.clone(), revealed));
+
+        let player_name = player.name.clone();
+        if let Some(player) = self.players.iter_mut().find(|p| p.client_id == player_id) {
+            player.hints_used += 1;
+        }
+        if let HintPolicy::Cost(cost) = self.config.hints {
+            self.update_score(player_id, -cost);
+        }
+        self.events
+            .push(Event::new(EventType::HintUsed, player_name));
+
+        Ok(set[..revealed].to_vec())
+    }
+
+    /// Deals up to three more cards onto the board. Returns how many were dealt.
+    pub fn add_cards(&mut self) -> usize {
+        let mut dealt = 0;
+        while dealt < 3 {
+            let Some(card) = self.deck.draw() else {
+                break;
+            };
+            self.in_play.push(card);
+            dealt += 1;
+        }
+        self.remaining = self.deck.cards.len() as i64;
+        dealt
     }
 
-    pub fn add_cards(&mut self) {
-        let mut cards = self.deck.cards.drain(0..3).collect::<Vec<_>>();
-        self.in_play.append(&mut cards);
+    /// Shuffles the found sets back into the deck once it has run out.
+    pub fn reshuffle(&mut self) -> bool {
+        if !self.deck.cards.is_empty() || self.discard.is_empty() {
+            return false;
+        }
+
+        self.deck.cards.append(&mut self.discard);
+        self.deck.shuffle(&mut self.rng);
         self.remaining = self.deck.cards.len() as i64;
+        self.events.push(Event::new(
+            EventType::DeckReshuffled,
+            self.remaining.to_string(),
+        ));
+        true
+    }
+
+    /// With `auto_deal` enabled, keeps dealing three cards at a time while the
+    /// board has no set, reshuffling the found sets back in when the deck runs
+    /// out. Classic games end on an exhausted deck, so only games played to a
+    /// target score ever reshuffle.
+    pub fn auto_deal(&mut self) {
+        if !self.config.auto_deal || self.state != GameState::InProgress {
+            return;
+        }
+
+        while self.check_remaining_sets() {
+            if self.deck.cards.is_empty()
+                && (self.config.target_score.is_none() || !self.reshuffle())
+            {
+                break;
+            }
+
+            let dealt = self.add_cards();
+            self.events.push(Event::new(
+                EventType::CardsDealt,
+                format!("Dealt {} cards because the board had no set", dealt),
+            ));
+        }
+    }
+
+    /// Applies the room's penalty policy to a player who called a wrong set and
+    /// records it so everyone in the room can see it.
+    fn penalize(&mut self, player_id: u16, now: u64) {
+        let Some(player) = self.players.iter_mut().find(|p| p.client_id == player_id) else {
+            return;
+        };
+
+        // A player without the points to lose is locked out instead, or guessing
+        // at zero points would cost nothing.
+        let penalty = match self.config.penalty {
+            PenaltyPolicy::Deduction(points) if player.score < points => {
+                PenaltyPolicy::Lockout(DEFAULT_LOCKOUT_SECS)
+            }
+            penalty => penalty,
+        };
+        let data = match penalty {
+            PenaltyPolicy::None => return,
+            PenaltyPolicy::Deduction(points) => {
+                player.score -= points;
+                format!(
+                    "Player {} lost {} point(s) for a wrong set",
+                    player.name, points
+                )
+            }
+            PenaltyPolicy::Lockout(secs) => {
+                player.locked_until = Some(now + secs * 1000);
+                format!(
+                    "Player {} is locked out for {} seconds after a wrong set",
+                    player.name, secs
+                )
+            }
+        };
+
+        self.events
+            .push(Event::new(EventType::PlayerPenalized, data));
     }
 
+    /// Adjusts a player's score. Scores never drop below zero.
     pub fn update_score(&mut self, player_id: u16, value: i64) {
         let player = self.players.iter_mut().find(|p| p.client_id == player_id);
         if let Some(player) = player {
-            if player.score == 0 && value < 0 {
-                return;
-            }
-            player.score += value;
+            player.score = (player.score + value).max(0);
         }
     }
 
     pub fn find_index(&self, card: &Card) -> Option<usize> {
         self.in_play.iter().position(|c| c == card)
     }
+    /// Clears the board for a rematch. The rematch is dealt from a fresh seed,
+    /// since the old one is revealed once the game is over.
     pub fn reset(&mut self) {
         self.in_play.clear();
-        self.deck = Deck::new();
-        self.deck.shuffle(); // Shuffle again, maybe implement by default inside the ::new method?
-        self.deal();
+        self.discard.clear();
+        if self.puzzle.is_none() {
+            self.seed = rand::random();
+            self.rng = ChaCha8Rng::seed_from_u64(self.seed);
+        }
+        self.deck = Deck::for_mode(&self.mode);
+        self.deck.shuffle(&mut self.rng);
+        self.remaining = self.deck.cards.len() as i64;
 
         self.game_over = None;
         self.last_player = None;
         self.last_set = None;
         self.state = GameState::WaitingForPlayers;
+        self.countdown_ends_at = None;
 
         for player in &mut self.players {
             player.score = 0;
+            player.request = false;
+            player.ready = false;
+            player.locked_until = None;
+            player.hints_used = 0;
         }
 
         self.events.clear();
+        self.hints.clear();
+        self.found.clear();
+        self.started_at = None;
+        self.ended_at = None;
     }
 }
 
-#[derive(Debug, Deserialize)]
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("Time went backwards")
+        .as_millis() as u64
+}
+
+#[derive(Debug, Clone, Deserialize, JsonSchema, PartialEq, Eq)]
 pub struct Move {
     pub room_code: String,
     pub cards: Vec<Card>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PLAYER: u16 = 1;
+
+    /// A started game of `mode` with a single player.
+    fn started(mode: GameMode, config: GameConfig) -> Game {
+        let mut game = Game::new(
+            mode,
+            GameConfig {
+                seed: Some(7),
+                ..config
+            },
+        );
+        game.add_player(Player::new(PLAYER, 100, "ada".to_string()));
+        let ends_at = game.begin_countdown(Duration::ZERO).unwrap();
+        assert!(game.start(ends_at));
+        game
+    }
+
+    fn a_set(game: &Game) -> Vec<Card> {
+        game.find_set().expect("the board holds a set")
+    }
+
+    fn not_a_set(game: &Game) -> Vec<Card> {
+        let board = &game.in_play;
+        for i in 0..board.len() {
+            for j in (i + 1)..board.len() {
+                for k in (j + 1)..board.len() {
+                    let cards = vec![board[i].clone(), board[j].clone(), board[k].clone()];
+                    if !game.rules().is_set(&cards) {
+                        return cards;
+                    }
+                }
+            }
+        }
+        panic!("every triple on the board is a set");
+    }
+
+    #[test]
+    fn accepts_a_set_and_scores_it() {
+        let mut game = started(GameMode::Classic, GameConfig::default());
+        let set = a_set(&game);
+        assert_eq!(game.make_move(PLAYER, &set), Ok(()));
+        assert_eq!(game.players[0].score, 1);
+        assert_eq!(game.discard, set);
+        assert!(set.iter().all(|card| game.find_index(card).is_none()));
+    }
+
+    #[test]
+    fn rejects_moves_before_the_game_starts() {
+        let mut game = Game::new(GameMode::Classic, GameConfig::default());
+        game.add_player(Player::new(PLAYER, 100, "ada".to_string()));
+        let cards = game.deck.cards[..3].to_vec();
+        assert_eq!(
+            game.make_move(PLAYER, &cards),
+            Err(MoveRejection::GameNotInProgress)
+        );
+    }
+
+    #[test]
+    fn rejects_moves_from_spectators() {
+        let mut game = started(GameMode::Classic, GameConfig::default());
+        let set = a_set(&game);
+        assert_eq!(
+            game.make_move(PLAYER + 1, &set),
+            Err(MoveRejection::NotAPlayer)
+        );
+    }
+
+    #[test]
+    fn rejects_the_wrong_number_of_cards() {
+        let mut game = started(GameMode::Classic, GameConfig::default());
+        let set = a_set(&game);
+        assert_eq!(
+            game.make_move(PLAYER, &set[..2]),
+            Err(MoveRejection::WrongCardCount)
+        );
+
+        let mut game = started(GameMode::Ultra, GameConfig::default());
+        let set = a_set(&game);
+        assert_eq!(
+            game.make_move(PLAYER, &set[..3]),
+            Err(MoveRejection::WrongCardCount)
+        );
+    }
+
+    #[test]
+    fn rejects_cards_that_are_not_on_the_board() {
+        let mut game = started(GameMode::Classic, GameConfig::default());
+        let mut cards = a_set(&game);
+        cards[2] = game.deck.cards[0].clone();
+        assert_eq!(
+            game.make_move(PLAYER, &cards),
+            Err(MoveRejection::CardNotInPlay)
+        );
+    }
+
+    #[test]
+    fn rejects_the_same_card_twice() {
+        let mut game = started(GameMode::Classic, GameConfig::default());
+        let mut cards = a_set(&game);
+        cards[2] = cards[0].clone();
+        assert_eq!(
+            game.make_move(PLAYER, &cards),
+            Err(MoveRejection::DuplicateCards)
+        );
+    }
+
+    #[test]
+    fn rejects_cards_that_are_not_a_set() {
+        let mut game = started(GameMode::Classic, GameConfig::default());
+        let board = game.in_play.clone();
+        let cards = not_a_set(&game);
+        assert_eq!(game.make_move(PLAYER, &cards), Err(MoveRejection::NotASet));
+        assert_eq!(game.in_play, board);
+        assert_eq!(game.players[0].score, 0);
+    }
+
+    #[test]
+    fn locks_out_a_player_after_a_wrong_call() {
+        let config = GameConfig {
+            penalty: PenaltyPolicy::Lockout(DEFAULT_LOCKOUT_SECS),
+            ..GameConfig::default()
+        };
+        let mut game = started(GameMode::Classic, config);
+        let cards = not_a_set(&game);
+        assert_eq!(game.make_move(PLAYER, &cards), Err(MoveRejection::NotASet));
+
+        let set = a_set(&game);
+        assert_eq!(game.make_move(PLAYER, &set), Err(MoveRejection::LockedOut));
+    }
+
+    #[test]
+    fn rejects_a_daily_set_found_before() {
+        let mut game = started(GameMode::Daily, GameConfig::default());
+        let set = a_set(&game);
+        assert_eq!(game.make_move(PLAYER, &set), Ok(()));
+
+        let mut reordered = set.clone();
+        reordered.reverse();
+        assert_eq!(
+            game.make_move(PLAYER, &reordered),
+            Err(MoveRejection::AlreadyFound)
+        );
+        assert_eq!(game.players[0].score, 1);
+    }
+}
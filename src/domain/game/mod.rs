@@ -1,5 +1,12 @@
 pub mod card;
+pub mod compact;
+pub mod config;
+pub mod daily;
 pub mod deck;
 #[allow(clippy::module_inception)]
 pub mod game;
 pub mod player;
+pub mod proset;
+pub mod rules;
+pub mod solver;
+pub mod view;
@@ -1,20 +1,33 @@
 use serde::Serialize;
 
+/// How other clients refer to a player or spectator. It is drawn at random when
+/// they first join a room, so it reveals nothing about their session.
+pub type PlayerId = u32;
+
 #[derive(Debug, Clone, Serialize, PartialEq, Eq)]
 pub struct Player {
+    #[serde(skip)]
     pub client_id: u16,
+    pub id: PlayerId,
     pub name: String,
     pub score: i64,
     pub request: bool,
+    pub ready: bool,
+    pub locked_until: Option<u64>, // milliseconds since the unix epoch
+    pub hints_used: u32,
 }
 
 impl Player {
-    pub fn new(client_id: u16, name: String) -> Self {
+    pub fn new(client_id: u16, id: PlayerId, name: String) -> Self {
         Player {
             client_id,
+            id,
             name,
             score: 0,
             request: false,
+            ready: false,
+            locked_until: None,
+            hints_used: 0,
         }
     }
 }
@@ -0,0 +1,171 @@
+use schemars::JsonSchema;
+use serde::Serialize;
+
+/// Every combination of dots except the empty one.
+pub const PROSET_CARD_COUNT: u8 = 63;
+const DOTS: usize = 6;
+
+/// A ProSET card: up to six dots, each of a different color. Bit `i` of `dots`
+/// is set when the dot of color `i` is on the card. A set is any group of cards
+/// on which every color shows up an even number of times, i.e. whose dots XOR to
+/// zero.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, JsonSchema)]
+pub struct ProsetCard {
+    #[schemars(range(min = 1, max = 63))]
+    dots: u8,
+}
+
+impl ProsetCard {
+    pub fn new(dots: u8) -> Option<Self> {
+        (1..=PROSET_CARD_COUNT)
+            .contains(&dots)
+            .then_some(Self { dots })
+    }
+
+    pub fn dots(self) -> u8 {
+        self.dots
+    }
+
+    /// The whole 63-card deck, in order of `dots`.
+    pub fn deck() -> Vec<Self> {
+        (1..=PROSET_CARD_COUNT).map(|dots| Self { dots }).collect()
+    }
+}
+
+/// Whether the distinct cards form a set. Two distinct cards never XOR to zero,
+/// so every set has at least three cards.
+pub fn is_set(cards: &[ProsetCard]) -> bool {
+    !cards.is_empty() && cards.iter().fold(0, |dots, card| dots ^ card.dots) == 0
+}
+
+/// The indices of a set among `cards`, if there is one. Cards are reduced
+/// against the ones before them one at a time; the first card that reduces to
+/// no dots at all is the XOR of some earlier cards, and together they form a
+/// set.
+pub fn first_set(cards: &[ProsetCard]) -> Option<Vec<usize>> {
+    let mut set = None;
+    reduce(cards, |used| {
+        set = Some((0..cards.len()).filter(|&i| used & (1 << i) != 0).collect());
+        false
+    });
+    set
+}
+
+/// How many sets there are among the distinct `cards`. Every subset of the
+/// cards that reduced to nothing XORs to zero, so with `r` independent cards
+/// out of `n` there are `2^(n - r) - 1` sets.
+pub fn count_sets(cards: &[ProsetCard]) -> usize {
+    let mut dependent: u32 = 0;
+    reduce(cards, |_| {
+        dependent += 1;
+        true
+    });
+    1usize
+        .checked_shl(dependent)
+        .map_or(usize::MAX, |subsets| subsets - 1)
+}
+
+/// Runs Gaussian elimination over the dots of `cards`, calling `f` with a mask
+/// of the card indices that XOR to zero whenever a card turns out to depend on
+/// the ones before it, until `f` returns `false`.
+fn reduce(cards: &[ProsetCard], mut f: impl FnMut(u64) -> bool) {
+    // Indexed by the highest dot: the reduced dots, and the cards XORed into them.
+    let mut basis: [Option<(u8, u64)>; DOTS] = [None; DOTS];
+    for (index, card) in cards.iter().enumerate() {
+        let mut dots = card.dots;
+        let mut used = 1u64 << index;
+        while dots != 0 {
+            let highest = (u8::BITS - 1 - dots.leading_zeros()) as usize;
+            match basis[highest] {
+                Some((basis_dots, basis_used)) => {
+                    dots ^= basis_dots;
+                    used ^= basis_used;
+                }
+                None => {
+                    basis[highest] = Some((dots, used));
+                    break;
+                }
+            }
+        }
+        if dots == 0 && !f(used) {
+            return;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{seq::SliceRandom, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+
+    use super::*;
+
+    /// Every non-empty subset of `cards` whose dots XOR to zero, as index masks.
+    fn sets_by_hand(cards: &[ProsetCard]) -> Vec<u64> {
+        (1..1u64 << cards.len())
+            .filter(|mask| {
+                (0..cards.len())
+                    .filter(|i| mask & (1 << i) != 0)
+                    .fold(0, |dots, i| dots ^ cards[i].dots)
+                    == 0
+            })
+            .collect()
+    }
+
+    fn boards(size: usize, count: usize) -> impl Iterator<Item = Vec<ProsetCard>> {
+        let mut rng = ChaCha8Rng::seed_from_u64(25);
+        (0..count).map(move |_| {
+            let mut cards = ProsetCard::deck();
+            cards.shuffle(&mut rng);
+            cards.truncate(size);
+            cards
+        })
+    }
+
+    #[test]
+    fn counts_the_sets_found_by_checking_every_subset() {
+        for size in [3, 4, 5, 6, 7, 8, 10] {
+            for board in boards(size, 200) {
+                let sets = sets_by_hand(&board);
+                assert_eq!(count_sets(&board), sets.len());
+                match first_set(&board) {
+                    Some(set) => {
+                        assert!(set.len() >= 3);
+                        assert!(sets.contains(&set.iter().fold(0, |mask, i| mask | 1 << i)));
+                    }
+                    None => assert!(sets.is_empty()),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn any_seven_cards_hold_a_set() {
+        for board in boards(7, 1000) {
+            let set = first_set(&board).expect("seven cards always hold a set");
+            assert!(is_set(&set.iter().map(|&i| board[i]).collect::<Vec<_>>()));
+        }
+    }
+
+    #[test]
+    fn the_deck_spans_six_dots() {
+        // 63 cards, 6 of them independent.
+        assert_eq!(count_sets(&ProsetCard::deck()), (1 << 57) - 1);
+    }
+
+    #[test]
+    fn is_set_needs_every_color_an_even_number_of_times() {
+        let card = |dots| ProsetCard::new(dots).unwrap();
+        assert!(is_set(&[card(0b001), card(0b010), card(0b011)]));
+        assert!(is_set(&[
+            card(0b001),
+            card(0b010),
+            card(0b100),
+            card(0b111)
+        ]));
+        assert!(!is_set(&[card(0b001), card(0b010), card(0b100)]));
+        assert!(!is_set(&[]));
+        assert_eq!(ProsetCard::new(0), None);
+        assert_eq!(ProsetCard::new(PROSET_CARD_COUNT + 1), None);
+    }
+}
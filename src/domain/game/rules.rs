@@ -0,0 +1,281 @@
+use std::fmt;
+
+use super::{
+    card::{Card, ClassicCard, Shading},
+    compact::{CompactCard, CARD_COUNT},
+    game::GameMode,
+    proset::{self, ProsetCard},
+    solver,
+};
+
+/// The rules of a card family: the cards it is played with and what makes a
+/// set. [`Game`](super::game::Game) and [`Deck`](super::deck::Deck) only go
+/// through these, so a new variant is a new implementation.
+pub trait Rules: fmt::Debug + Send + Sync {
+    /// Every card of the deck, unshuffled.
+    fn cards(&self) -> Vec<Card>;
+
+    /// How many cards are dealt at the start of a game.
+    fn board_size(&self) -> usize;
+
+    /// Whether a selection of `count` cards can be a set at all.
+    fn accepts(&self, count: usize) -> bool;
+
+    /// Whether the distinct cards form a set.
+    fn is_set(&self, cards: &[Card]) -> bool;
+
+    /// The indices of the first set among `cards`, in ascending order.
+    fn first_set(&self, cards: &[Card]) -> Option<Vec<usize>>;
+
+    fn count_sets(&self, cards: &[Card]) -> usize;
+}
+
+/// The rules a game of `mode` is played by.
+pub fn for_mode(mode: &GameMode) -> &'static dyn Rules {
+    match mode {
+        GameMode::Classic | GameMode::BestOf3 | GameMode::Race | GameMode::Daily => &Classic,
+        GameMode::Ultra => &Ultra,
+        GameMode::Junior => &Junior,
+        GameMode::Proset => &Proset,
+    }
+}
+
+/// Sets of three from the 81-card deck.
+#[derive(Debug)]
+pub struct Classic;
+
+impl Rules for Classic {
+    fn cards(&self) -> Vec<Card> {
+        (0..CARD_COUNT)
+            .map(|index| CompactCard(index).into())
+            .collect()
+    }
+
+    fn board_size(&self) -> usize {
+        12
+    }
+
+    fn accepts(&self, count: usize) -> bool {
+        count == 3
+    }
+
+    fn is_set(&self, cards: &[Card]) -> bool {
+        solver::is_set(cards)
+    }
+
+    fn first_set(&self, cards: &[Card]) -> Option<Vec<usize>> {
+        solver::first_set(cards).map(Vec::from)
+    }
+
+    fn count_sets(&self, cards: &[Card]) -> usize {
+        solver::count_sets(cards)
+    }
+}
+
+/// Ultra SET: four cards that split into two pairs completed by the same card.
+#[derive(Debug)]
+pub struct Ultra;
+
+impl Rules for Ultra {
+    fn cards(&self) -> Vec<Card> {
+        Classic.cards()
+    }
+
+    fn board_size(&self) -> usize {
+        12
+    }
+
+    fn accepts(&self, count: usize) -> bool {
+        count == 4
+    }
+
+    fn is_set(&self, cards: &[Card]) -> bool {
+        solver::is_ultra_set(cards)
+    }
+
+    fn first_set(&self, cards: &[Card]) -> Option<Vec<usize>> {
+        solver::first_ultra_set(cards).map(Vec::from)
+    }
+
+    fn count_sets(&self, cards: &[Card]) -> usize {
+        solver::count_ultra_sets(cards)
+    }
+}
+
+/// SET Junior: the 27 solid cards on a board of nine. With the shading fixed,
+/// the classic rule only ever looks at the other three attributes.
+#[derive(Debug)]
+pub struct Junior;
+
+impl Rules for Junior {
+    fn cards(&self) -> Vec<Card> {
+        Classic
+            .cards()
+            .into_iter()
+            .filter(|card| {
+                matches!(card, Card::Classic(ClassicCard { shading, .. }) if *shading == Shading::Solid)
+            })
+            .collect()
+    }
+
+    fn board_size(&self) -> usize {
+        9
+    }
+
+    fn accepts(&self, count: usize) -> bool {
+        Classic.accepts(count)
+    }
+
+    fn is_set(&self, cards: &[Card]) -> bool {
+        Classic.is_set(cards)
+    }
+
+    fn first_set(&self, cards: &[Card]) -> Option<Vec<usize>> {
+        Classic.first_set(cards)
+    }
+
+    fn count_sets(&self, cards: &[Card]) -> usize {
+        Classic.count_sets(cards)
+    }
+}
+
+/// ProSET: any group of three or more of the 63 dot cards whose dots XOR to
+/// zero. Any seven cards hold a set, so seven are dealt.
+#[derive(Debug)]
+pub struct Proset;
+
+impl Proset {
+    /// The ProSET cards among `cards`, or `None` if any card is from another
+    /// family.
+    fn proset_cards(cards: &[Card]) -> Option<Vec<ProsetCard>> {
+        cards
+            .iter()
+            .map(|card| match card {
+                Card::Proset(card) => Some(*card),
+                Card::Classic(_) => None,
+            })
+            .collect()
+    }
+}
+
+impl Rules for Proset {
+    fn cards(&self) -> Vec<Card> {
+        ProsetCard::deck().into_iter().map(Card::from).collect()
+    }
+
+    fn board_size(&self) -> usize {
+        7
+    }
+
+    fn accepts(&self, count: usize) -> bool {
+        count >= 3
+    }
+
+    fn is_set(&self, cards: &[Card]) -> bool {
+        Self::proset_cards(cards).is_some_and(|cards| proset::is_set(&cards))
+    }
+
+    fn first_set(&self, cards: &[Card]) -> Option<Vec<usize>> {
+        proset::first_set(&Self::proset_cards(cards)?)
+    }
+
+    fn count_sets(&self, cards: &[Card]) -> usize {
+        Self::proset_cards(cards).map_or(0, |cards| proset::count_sets(&cards))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ahash::HashSet;
+    use rand::{seq::SliceRandom, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+
+    use super::*;
+
+    fn classic(indices: &[u8]) -> Vec<Card> {
+        indices.iter().map(|&i| CompactCard(i).into()).collect()
+    }
+
+    #[test]
+    fn decks_and_boards_per_mode() {
+        for (mode, cards, board_size) in [
+            (GameMode::Classic, 81, 12),
+            (GameMode::Daily, 81, 12),
+            (GameMode::Ultra, 81, 12),
+            (GameMode::Junior, 27, 9),
+            (GameMode::Proset, 63, 7),
+        ] {
+            let rules = for_mode(&mode);
+            let deck = rules.cards();
+            assert_eq!(deck.len(), cards, "{:?}", mode);
+            assert_eq!(
+                deck.iter().collect::<HashSet<_>>().len(),
+                cards,
+                "{:?}",
+                mode
+            );
+            assert_eq!(rules.board_size(), board_size, "{:?}", mode);
+        }
+    }
+
+    #[test]
+    fn junior_plays_the_solid_cards() {
+        let deck = Junior.cards();
+        assert!(deck.iter().all(|card| matches!(
+            card,
+            Card::Classic(ClassicCard {
+                shading: Shading::Solid,
+                ..
+            })
+        )));
+        // Any two solid cards are completed by a third solid card.
+        assert_eq!(Junior.count_sets(&deck), 27 * 26 / 6);
+    }
+
+    #[test]
+    fn junior_boards_count_like_classic_ones() {
+        let mut rng = ChaCha8Rng::seed_from_u64(24);
+        for _ in 0..200 {
+            let mut board = Junior.cards();
+            board.shuffle(&mut rng);
+            board.truncate(Junior.board_size());
+            let count = Junior.count_sets(&board);
+            assert_eq!(count, solver::find_sets(&board).len());
+            assert_eq!(Junior.first_set(&board).is_some(), count > 0);
+        }
+    }
+
+    #[test]
+    fn ultra_groups_pairs_by_their_third_card() {
+        // 0 and 1 are completed by card 2, and so are 3 and 7.
+        let set = classic(&[0, 1, 3, 7]);
+        assert!(Ultra.accepts(4) && !Ultra.accepts(3));
+        assert!(Ultra.is_set(&set));
+        assert!(Ultra.is_set(&classic(&[7, 3, 1, 0])));
+        assert!(!Classic.is_set(&set));
+        assert_eq!(Ultra.first_set(&set), Some(vec![0, 1, 2, 3]));
+        assert_eq!(Ultra.count_sets(&set), 1);
+
+        // A classic set and a fourth card never pair up.
+        let not_a_set = classic(&[0, 1, 2, 3]);
+        assert!(!Ultra.is_set(&not_a_set));
+        assert_eq!(Ultra.first_set(&not_a_set), None);
+    }
+
+    #[test]
+    fn proset_deals_seven_cards_that_always_hold_a_set() {
+        let mut rng = ChaCha8Rng::seed_from_u64(25);
+        for _ in 0..1000 {
+            let mut board = Proset.cards();
+            board.shuffle(&mut rng);
+            board.truncate(Proset.board_size());
+            let set = Proset
+                .first_set(&board)
+                .expect("seven cards always hold a set");
+            let cards: Vec<_> = set.iter().map(|&i| board[i].clone()).collect();
+            assert!(Proset.accepts(cards.len()));
+            assert!(Proset.is_set(&cards));
+        }
+        assert!(!Proset.is_set(&classic(&[0, 1, 2])));
+    }
+}
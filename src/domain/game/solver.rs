@@ -0,0 +1,274 @@
+//! Sets among classic cards. Cards of other families never form a set here.
+
+use super::{
+    card::Card,
+    compact::{CompactCard, CARD_COUNT},
+};
+
+/// Whether the cards are three distinct cards forming a set.
+pub fn is_set(cards: &[Card]) -> bool {
+    match compact(cards).as_deref() {
+        Some(&[a, b, c]) => CompactCard::is_set(a, b, c),
+        _ => false,
+    }
+}
+
+/// Every set among `cards`, as ascending index triples in board order. Each
+/// pair is completed by looking its third card up, so this runs in O(n²).
+pub fn find_sets(cards: &[Card]) -> Vec<[usize; 3]> {
+    let mut sets = Vec::new();
+    for_each_set(cards, |set| {
+        sets.push(set);
+        true
+    });
+    sets
+}
+
+/// The first set among `cards`, if there is one.
+pub fn first_set(cards: &[Card]) -> Option<[usize; 3]> {
+    let mut first = None;
+    for_each_set(cards, |set| {
+        first = Some(set);
+        false
+    });
+    first
+}
+
+pub fn count_sets(cards: &[Card]) -> usize {
+    let mut count = 0;
+    for_each_set(cards, |_| {
+        count += 1;
+        true
+    });
+    count
+}
+
+/// Whether the cards are four distinct cards that split into two pairs
+/// completed by the same third card. That card need not be among them.
+pub fn is_ultra_set(cards: &[Card]) -> bool {
+    let Some(&[a, b, c, d]) = compact(cards).as_deref() else {
+        return false;
+    };
+    if a == b || a == c || a == d || b == c || b == d || c == d {
+        return false;
+    }
+    a.third(b) == c.third(d) || a.third(c) == b.third(d) || a.third(d) == b.third(c)
+}
+
+pub fn first_ultra_set(cards: &[Card]) -> Option<[usize; 4]> {
+    let mut first = None;
+    for_each_ultra_set(cards, |set| {
+        first = Some(set);
+        false
+    });
+    first
+}
+
+pub fn count_ultra_sets(cards: &[Card]) -> usize {
+    let mut count = 0;
+    for_each_ultra_set(cards, |_| {
+        count += 1;
+        true
+    });
+    count
+}
+
+/// Calls `f` with every ultra set among `cards` until it returns `false`. Pairs
+/// are grouped by the card that completes them; two distinct pairs completed by
+/// the same card never share a card, so any two pairs of a group form an ultra
+/// set, and no four cards pair up in more than one way.
+fn for_each_ultra_set(cards: &[Card], mut f: impl FnMut([usize; 4]) -> bool) {
+    let compact = compact(cards).unwrap_or_default();
+    let mut pairs: Vec<Vec<(usize, usize)>> = vec![vec![]; CARD_COUNT as usize];
+    for i in 0..compact.len() {
+        for j in (i + 1)..compact.len() {
+            pairs[compact[i].third(compact[j]).index() as usize].push((i, j));
+        }
+    }
+
+    for group in &pairs {
+        for (n, &(a, b)) in group.iter().enumerate() {
+            for &(c, d) in &group[n + 1..] {
+                let mut set = [a, b, c, d];
+                set.sort_unstable();
+                if !f(set) {
+                    return;
+                }
+            }
+        }
+    }
+}
+
+/// Calls `f` with every set among `cards` until it returns `false`.
+fn for_each_set(cards: &[Card], mut f: impl FnMut([usize; 3]) -> bool) {
+    let compact = compact(cards).unwrap_or_default();
+    let mut positions = [None; CARD_COUNT as usize];
+    for (index, card) in compact.iter().enumerate() {
+        positions[card.index() as usize] = Some(index);
+    }
+
+    for i in 0..compact.len() {
+        for j in (i + 1)..compact.len() {
+            let third = compact[i].third(compact[j]);
+            if let Some(k) = positions[third.index() as usize] {
+                if k > j && !f([i, j, k]) {
+                    return;
+                }
+            }
+        }
+    }
+}
+
+/// The cards as compact indices, or `None` if any of them is not a classic card.
+fn compact(cards: &[Card]) -> Option<Vec<CompactCard>> {
+    cards.iter().map(CompactCard::of).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{seq::SliceRandom, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+
+    use super::*;
+    use crate::domain::game::{
+        card::ClassicCard,
+        rules::{Classic, Proset, Rules},
+    };
+
+    fn classic(card: &Card) -> ClassicCard {
+        match card {
+            Card::Classic(card) => *card,
+            Card::Proset(_) => panic!("not a classic card"),
+        }
+    }
+
+    /// The rule as printed on the box: on every attribute, the three cards are
+    /// all the same or all different.
+    fn is_set_by_hand(a: &Card, b: &Card, c: &Card) -> bool {
+        let attributes = |card: &Card| {
+            let card = classic(card);
+            [
+                card.shape as u8,
+                card.color as u8,
+                card.number as u8,
+                card.shading as u8,
+            ]
+        };
+        let (a, b, c) = (attributes(a), attributes(b), attributes(c));
+        (0..4).all(|i| {
+            (a[i] == b[i] && b[i] == c[i]) || (a[i] != b[i] && b[i] != c[i] && a[i] != c[i])
+        })
+    }
+
+    fn sets_by_hand(cards: &[Card]) -> Vec<[usize; 3]> {
+        let mut sets = Vec::new();
+        for i in 0..cards.len() {
+            for j in (i + 1)..cards.len() {
+                for k in (j + 1)..cards.len() {
+                    if is_set_by_hand(&cards[i], &cards[j], &cards[k]) {
+                        sets.push([i, j, k]);
+                    }
+                }
+            }
+        }
+        sets
+    }
+
+    /// Whether two of the pairs of four cards are completed by the same card of
+    /// the deck, found by trying every card.
+    fn is_ultra_set_by_hand(cards: [&Card; 4], deck: &[Card]) -> bool {
+        let [a, b, c, d] = cards;
+        [(a, b, c, d), (a, c, b, d), (a, d, b, c)]
+            .into_iter()
+            .any(|(a, b, c, d)| {
+                deck.iter()
+                    .any(|x| is_set_by_hand(a, b, x) && is_set_by_hand(c, d, x))
+            })
+    }
+
+    fn boards(size: usize, count: usize) -> impl Iterator<Item = Vec<Card>> {
+        let mut rng = ChaCha8Rng::seed_from_u64(19);
+        (0..count).map(move |_| {
+            let mut cards = Classic.cards();
+            cards.shuffle(&mut rng);
+            cards.truncate(size);
+            cards
+        })
+    }
+
+    #[test]
+    fn the_deck_holds_1080_sets() {
+        let deck = Classic.cards();
+        assert_eq!(count_sets(&deck), 1080);
+        assert_eq!(find_sets(&deck), sets_by_hand(&deck));
+    }
+
+    #[test]
+    fn finds_the_sets_found_by_checking_every_triple() {
+        for board in boards(12, 200) {
+            let sets = sets_by_hand(&board);
+            assert_eq!(find_sets(&board), sets);
+            assert_eq!(first_set(&board), sets.first().copied());
+            assert_eq!(count_sets(&board), sets.len());
+            for [i, j, k] in sets {
+                assert!(is_set(&[
+                    board[i].clone(),
+                    board[j].clone(),
+                    board[k].clone()
+                ]));
+            }
+        }
+    }
+
+    #[test]
+    fn is_set_rejects_repeated_and_foreign_cards() {
+        let deck = Classic.cards();
+        assert!(!is_set(&[
+            deck[0].clone(),
+            deck[0].clone(),
+            deck[0].clone()
+        ]));
+        assert!(!is_set(&deck[..2]));
+        assert!(!is_set(&deck[..4]));
+        // Dots 1, 2 and 3 are a ProSET set, but no classic one.
+        assert!(!is_set(&Proset.cards()[..3]));
+    }
+
+    #[test]
+    fn finds_the_ultra_sets_found_by_checking_every_quadruple() {
+        let deck = Classic.cards();
+        for board in boards(12, 20) {
+            let mut sets = Vec::new();
+            for a in 0..board.len() {
+                for b in (a + 1)..board.len() {
+                    for c in (b + 1)..board.len() {
+                        for d in (c + 1)..board.len() {
+                            let cards = [&board[a], &board[b], &board[c], &board[d]];
+                            let expected = is_ultra_set_by_hand(cards, &deck);
+                            assert_eq!(is_ultra_set(&cards.map(Card::clone)), expected);
+                            if expected {
+                                sets.push([a, b, c, d]);
+                            }
+                        }
+                    }
+                }
+            }
+
+            let mut found = Vec::new();
+            for_each_ultra_set(&board, |set| {
+                found.push(set);
+                true
+            });
+            found.sort_unstable();
+            assert_eq!(found, sets);
+            assert_eq!(count_ultra_sets(&board), sets.len());
+            assert_eq!(first_ultra_set(&board).is_some(), !sets.is_empty());
+        }
+    }
+
+    #[test]
+    fn the_deck_holds_an_ultra_set_for_every_two_pairs_sharing_a_third_card() {
+        // Every card completes 40 pairs of the other 80 cards.
+        assert_eq!(count_ultra_sets(&Classic.cards()), 81 * (40 * 39 / 2));
+    }
+}
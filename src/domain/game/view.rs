@@ -0,0 +1,140 @@
+use serde::Serialize;
+
+use crate::domain::vote::{VoteThreshold, VoteView};
+
+use super::{
+    card::Card,
+    config::{HintPolicy, PenaltyPolicy},
+    daily::date,
+    game::{Event, Game, GameMode, GameState},
+    player::{Player, PlayerId},
+};
+
+/// The client-facing projection of a [`Game`]. This is the only game state that
+/// ever travels over the socket; the deck order and the reconnection bookkeeping
+/// stay on the server. Cards are written as `C`, see [`GameView::map_cards`].
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct GameView<C = Card> {
+    pub game_over: Option<bool>,
+    pub in_play: Vec<C>,
+    pub last_player: Option<String>,
+    pub last_set: Option<Vec<C>>,
+    pub players: Vec<Player>,
+    pub remaining: i64,
+    pub sets_on_board: usize,
+    pub state: GameState,
+    pub countdown_ends_at: Option<u64>,
+    pub mode: GameMode,
+    pub target_score: Option<i64>,
+    pub penalty: PenaltyPolicy,
+    pub auto_deal: bool,
+    pub hints: HintPolicy,
+    pub seed: Option<u64>, // revealed once the game is over, so the deck can't be predicted
+    pub puzzle: Option<PuzzleView<C>>, // only set in daily games
+    pub events: Vec<Event>,
+    pub host: Option<PlayerId>,
+    pub locked: bool,
+    pub spectators: usize,
+    pub max_players: usize,
+    pub votes: Vec<VoteView>,
+    pub vote_threshold: VoteThreshold,
+}
+
+/// The progress on a daily puzzle.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct PuzzleView<C = Card> {
+    pub day: u64,
+    pub date: String,
+    pub sets: usize,             // sets hidden on the board
+    pub found: Vec<Vec<C>>,      // sets found so far, in the order they were found
+    pub started_at: Option<u64>, // milliseconds since the unix epoch
+    pub solve_time: Option<u64>, // milliseconds, once every set has been found
+}
+
+impl From<&Game> for GameView {
+    fn from(game: &Game) -> Self {
+        Self {
+            game_over: game.game_over,
+            in_play: game.in_play.clone(),
+            last_player: game.last_player.clone(),
+            last_set: game.last_set.clone(),
+            players: game.players.clone(),
+            remaining: game.remaining,
+            sets_on_board: game.sets_on_board(),
+            state: game.state.clone(),
+            countdown_ends_at: game.countdown_ends_at,
+            mode: game.mode.clone(),
+            target_score: game.config.target_score,
+            penalty: game.config.penalty,
+            auto_deal: game.config.auto_deal,
+            hints: game.config.hints,
+            seed: (game.state == GameState::Ended).then_some(game.seed),
+            puzzle: game.puzzle.as_ref().map(|puzzle| PuzzleView {
+                day: puzzle.day,
+                date: date(puzzle.day),
+                sets: puzzle.sets,
+                found: game.found.clone(),
+                started_at: game.started_at,
+                solve_time: game.solve_time(),
+            }),
+            events: game.events.clone(),
+            host: None,
+            locked: false,
+            spectators: 0,
+            max_players: 0,
+            votes: vec![],
+            vote_threshold: VoteThreshold::default(),
+        }
+    }
+}
+
+impl<C> GameView<C> {
+    /// The same view with every card passed through `f`, e.g. to write the
+    /// cards in the encoding a client asked for.
+    pub fn map_cards<D>(self, mut f: impl FnMut(C) -> D) -> GameView<D> {
+        GameView {
+            game_over: self.game_over,
+            in_play: self.in_play.into_iter().map(&mut f).collect(),
+            last_player: self.last_player,
+            last_set: self
+                .last_set
+                .map(|set| set.into_iter().map(&mut f).collect()),
+            players: self.players,
+            remaining: self.remaining,
+            sets_on_board: self.sets_on_board,
+            state: self.state,
+            countdown_ends_at: self.countdown_ends_at,
+            mode: self.mode,
+            target_score: self.target_score,
+            penalty: self.penalty,
+            auto_deal: self.auto_deal,
+            hints: self.hints,
+            seed: self.seed,
+            puzzle: self.puzzle.map(|puzzle| puzzle.map_cards(&mut f)),
+            events: self.events,
+            host: self.host,
+            locked: self.locked,
+            spectators: self.spectators,
+            max_players: self.max_players,
+            votes: self.votes,
+            vote_threshold: self.vote_threshold,
+        }
+    }
+}
+
+impl<C> PuzzleView<C> {
+    pub fn map_cards<D>(self, mut f: impl FnMut(C) -> D) -> PuzzleView<D> {
+        PuzzleView {
+            day: self.day,
+            date: self.date,
+            sets: self.sets,
+            found: self
+                .found
+                .into_iter()
+                .map(|set| set.into_iter().map(&mut f).collect())
+                .collect(),
+            started_at: self.started_at,
+            solve_time: self.solve_time,
+        }
+    }
+}
@@ -0,0 +1,170 @@
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+use super::game::daily::{date, DAILY_SETS};
+
+/// Days of daily results kept, counting back from the newest result.
+pub const LEADERBOARD_DAYS: u64 = 30;
+/// Entries returned for a day.
+const LEADERBOARD_SIZE: usize = 100;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DailyResult {
+    pub client_id: u16, // from the server-issued session of the solver
+    pub name: String,
+    pub time_ms: u64, // from the start of the game to the last set found
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LeaderboardEntry {
+    pub rank: usize,
+    pub name: String,
+    pub time_ms: u64,
+}
+
+/// The leaderboard of one day as served by `/api/daily`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DailyLeaderboardView {
+    pub day: u64,
+    pub date: String,
+    pub sets: usize,
+    pub entries: Vec<LeaderboardEntry>,
+}
+
+/// Completion times of the daily puzzle, fastest first, for the last
+/// [`LEADERBOARD_DAYS`] days.
+#[derive(Debug, Default)]
+pub struct DailyLeaderboard {
+    days: BTreeMap<u64, Vec<DailyResult>>,
+}
+
+impl DailyLeaderboard {
+    /// Records a solved puzzle and returns its rank. Only the first solve of the
+    /// day counts, since replaying a board you have already seen is no race; a
+    /// later solve from the same session, or under the same name from a fresh
+    /// session, returns `None`.
+    pub fn record(&mut self, day: u64, result: DailyResult) -> Option<usize> {
+        let results = self.days.entry(day).or_default();
+        let name = result.name.to_lowercase();
+        if results
+            .iter()
+            .any(|r| r.client_id == result.client_id || r.name.to_lowercase() == name)
+        {
+            return None;
+        }
+
+        let index = results.partition_point(|r| r.time_ms <= result.time_ms);
+        results.insert(index, result);
+
+        if let Some(&newest) = self.days.keys().next_back() {
+            self.days.retain(|&d, _| d + LEADERBOARD_DAYS > newest);
+        }
+        Some(index + 1)
+    }
+
+    pub fn view(&self, day: u64) -> DailyLeaderboardView {
+        let entries = self
+            .days
+            .get(&day)
+            .map(|results| {
+                results
+                    .iter()
+                    .take(LEADERBOARD_SIZE)
+                    .enumerate()
+                    .map(|(index, r)| LeaderboardEntry {
+                        rank: index + 1,
+                        name: r.name.clone(),
+                        time_ms: r.time_ms,
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        DailyLeaderboardView {
+            day,
+            date: date(day),
+            sets: DAILY_SETS,
+            entries,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(client_id: u16, name: &str, time_ms: u64) -> DailyResult {
+        DailyResult {
+            client_id,
+            name: name.to_string(),
+            time_ms,
+        }
+    }
+
+    fn names(leaderboard: &DailyLeaderboard, day: u64) -> Vec<String> {
+        leaderboard
+            .view(day)
+            .entries
+            .into_iter()
+            .map(|entry| entry.name)
+            .collect()
+    }
+
+    #[test]
+    fn ranks_solves_fastest_first() {
+        let mut leaderboard = DailyLeaderboard::default();
+        assert_eq!(leaderboard.record(1, result(1, "ada", 30_000)), Some(1));
+        assert_eq!(leaderboard.record(1, result(2, "bob", 10_000)), Some(1));
+        assert_eq!(leaderboard.record(1, result(3, "cy", 20_000)), Some(2));
+        // Ties go to whoever solved first.
+        assert_eq!(leaderboard.record(1, result(4, "di", 20_000)), Some(3));
+
+        assert_eq!(names(&leaderboard, 1), ["bob", "cy", "di", "ada"]);
+        let ranks: Vec<usize> = leaderboard.view(1).entries.iter().map(|e| e.rank).collect();
+        assert_eq!(ranks, [1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn only_the_first_solve_of_a_session_counts() {
+        let mut leaderboard = DailyLeaderboard::default();
+        assert_eq!(leaderboard.record(1, result(1, "ada", 30_000)), Some(1));
+        assert_eq!(leaderboard.record(1, result(1, "grace", 5_000)), None);
+        assert_eq!(names(&leaderboard, 1), ["ada"]);
+
+        // The next day is a new puzzle.
+        assert_eq!(leaderboard.record(2, result(1, "ada", 25_000)), Some(1));
+    }
+
+    #[test]
+    fn a_fresh_session_cannot_solve_again_under_the_same_name() {
+        let mut leaderboard = DailyLeaderboard::default();
+        assert_eq!(leaderboard.record(1, result(1, "Ada", 30_000)), Some(1));
+        assert_eq!(leaderboard.record(1, result(2, "ada", 5_000)), None);
+        assert_eq!(names(&leaderboard, 1), ["Ada"]);
+    }
+
+    #[test]
+    fn keeps_only_the_last_days() {
+        let mut leaderboard = DailyLeaderboard::default();
+        leaderboard.record(1, result(1, "ada", 30_000));
+        leaderboard.record(LEADERBOARD_DAYS, result(1, "ada", 30_000));
+        assert_eq!(names(&leaderboard, 1), ["ada"]);
+
+        leaderboard.record(LEADERBOARD_DAYS + 1, result(1, "ada", 30_000));
+        assert!(names(&leaderboard, 1).is_empty());
+        assert_eq!(names(&leaderboard, LEADERBOARD_DAYS), ["ada"]);
+    }
+
+    #[test]
+    fn caps_the_entries_of_a_day() {
+        let mut leaderboard = DailyLeaderboard::default();
+        for client_id in 0..=LEADERBOARD_SIZE as u16 {
+            let name = format!("player {}", client_id);
+            leaderboard.record(1, result(client_id, &name, client_id as u64));
+        }
+        let view = leaderboard.view(1);
+        assert_eq!(view.entries.len(), LEADERBOARD_SIZE);
+        assert_eq!(view.sets, DAILY_SETS);
+    }
+}
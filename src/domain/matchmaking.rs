@@ -0,0 +1,181 @@
+use std::time::{Duration, Instant};
+
+use super::game::game::GameMode;
+
+pub const MIN_MATCH_PLAYERS: usize = 2;
+pub const MAX_MATCH_PLAYERS: usize = 8;
+const MATCH_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MatchRequest {
+    pub client_id: u16,
+    pub player_username: String,
+    pub mode: GameMode,
+    pub players: usize, // desired match size
+    pub queued_at: Instant,
+}
+
+/// Clients waiting for a quick match, in the order they queued.
+#[derive(Debug, Default)]
+pub struct MatchmakingQueue {
+    waiting: Vec<MatchRequest>,
+}
+
+impl MatchmakingQueue {
+    pub fn new() -> Self {
+        Self { waiting: vec![] }
+    }
+
+    /// Queues a request, replacing any earlier one from the same client.
+    pub fn enqueue(&mut self, request: MatchRequest) {
+        self.remove(request.client_id);
+        self.waiting.push(request);
+    }
+
+    pub fn remove(&mut self, client_id: u16) -> bool {
+        let before = self.waiting.len();
+        self.waiting.retain(|r| r.client_id != client_id);
+        self.waiting.len() != before
+    }
+
+    /// Takes every match that can be formed right now. Requests are grouped by
+    /// mode and desired size; once a request has waited longer than
+    /// `MATCH_TIMEOUT` it settles for anyone queued for the same mode.
+    pub fn take_matches(&mut self, now: Instant) -> Vec<Vec<MatchRequest>> {
+        let mut matches = Vec::new();
+        while let Some(group) = self.next_match(now) {
+            matches.push(group);
+        }
+        matches
+    }
+
+    fn next_match(&mut self, now: Instant) -> Option<Vec<MatchRequest>> {
+        let indices = self.waiting.iter().find_map(|request| {
+            let exact: Vec<usize> = self
+                .waiting
+                .iter()
+                .enumerate()
+                .filter(|(_, r)| r.mode == request.mode && r.players == request.players)
+                .map(|(index, _)| index)
+                .take(request.players)
+                .collect();
+            if exact.len() == request.players {
+                return Some(exact);
+            }
+
+            if now.duration_since(request.queued_at) < MATCH_TIMEOUT {
+                return None;
+            }
+            let fallback: Vec<usize> = self
+                .waiting
+                .iter()
+                .enumerate()
+                .filter(|(_, r)| r.mode == request.mode)
+                .map(|(index, _)| index)
+                .take(request.players)
+                .collect();
+            (fallback.len() >= MIN_MATCH_PLAYERS).then_some(fallback)
+        })?;
+
+        // Indices are ascending, so removing back to front keeps them valid.
+        let mut group: Vec<MatchRequest> = indices
+            .into_iter()
+            .rev()
+            .map(|index| self.waiting.remove(index))
+            .collect();
+        group.reverse();
+        Some(group)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(client_id: u16, mode: GameMode, players: usize, queued_at: Instant) -> MatchRequest {
+        MatchRequest {
+            client_id,
+            player_username: format!("player {}", client_id),
+            mode,
+            players,
+            queued_at,
+        }
+    }
+
+    fn client_ids(matches: &[Vec<MatchRequest>]) -> Vec<Vec<u16>> {
+        matches
+            .iter()
+            .map(|group| group.iter().map(|r| r.client_id).collect())
+            .collect()
+    }
+
+    #[test]
+    fn matches_requests_for_the_same_mode_and_size_in_queue_order() {
+        let now = Instant::now();
+        let mut queue = MatchmakingQueue::new();
+        queue.enqueue(request(1, GameMode::Classic, 2, now));
+        queue.enqueue(request(2, GameMode::Race, 2, now));
+        queue.enqueue(request(3, GameMode::Classic, 3, now));
+        queue.enqueue(request(4, GameMode::Classic, 2, now));
+        queue.enqueue(request(5, GameMode::Classic, 2, now));
+
+        let matches = queue.take_matches(now);
+        assert_eq!(client_ids(&matches), vec![vec![1, 4]]);
+        assert!(queue.take_matches(now).is_empty());
+        assert_eq!(queue.waiting.len(), 3);
+    }
+
+    #[test]
+    fn takes_every_match_that_can_be_formed() {
+        let now = Instant::now();
+        let mut queue = MatchmakingQueue::new();
+        for client_id in 1..=4 {
+            queue.enqueue(request(client_id, GameMode::Classic, 2, now));
+        }
+        queue.enqueue(request(5, GameMode::Junior, 2, now));
+        queue.enqueue(request(6, GameMode::Junior, 2, now));
+
+        let matches = queue.take_matches(now);
+        assert_eq!(
+            client_ids(&matches),
+            vec![vec![1, 2], vec![3, 4], vec![5, 6]]
+        );
+        assert!(queue.waiting.is_empty());
+    }
+
+    #[test]
+    fn requeuing_replaces_the_earlier_request() {
+        let now = Instant::now();
+        let mut queue = MatchmakingQueue::new();
+        queue.enqueue(request(1, GameMode::Classic, 2, now));
+        queue.enqueue(request(1, GameMode::Classic, 2, now));
+        assert!(queue.take_matches(now).is_empty());
+
+        assert!(queue.remove(1));
+        assert!(!queue.remove(1));
+    }
+
+    #[test]
+    fn settles_for_any_size_after_the_timeout() {
+        let queued_at = Instant::now();
+        let mut queue = MatchmakingQueue::new();
+        queue.enqueue(request(1, GameMode::Classic, 4, queued_at));
+        queue.enqueue(request(2, GameMode::Classic, 3, queued_at));
+        queue.enqueue(request(3, GameMode::Ultra, 3, queued_at));
+
+        assert!(queue.take_matches(queued_at).is_empty());
+        let later = queued_at + MATCH_TIMEOUT;
+        assert_eq!(client_ids(&queue.take_matches(later)), vec![vec![1, 2]]);
+    }
+
+    #[test]
+    fn a_lone_request_never_matches() {
+        let queued_at = Instant::now();
+        let mut queue = MatchmakingQueue::new();
+        queue.enqueue(request(1, GameMode::Classic, 2, queued_at));
+        assert!(queue
+            .take_matches(queued_at + MATCH_TIMEOUT * 10)
+            .is_empty());
+        assert_eq!(queue.waiting.len(), 1);
+    }
+}
@@ -1,76 +1,377 @@
-use ahash::{HashMap, HashMapExt};
-use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 
-use crate::infra::error::Error;
+use super::{
+    chat::ChatMessage,
+    game::{
+        card::Card,
+        game::{GameMode, Move, MoveRejection},
+        player::PlayerId,
+        view::GameView,
+    },
+    matchmaking::{MAX_MATCH_PLAYERS, MIN_MATCH_PLAYERS},
+    room::{JoinRejection, RoomSummary},
+    vote::VoteKind,
+};
 
-#[derive(Debug, Serialize, Deserialize, Clone, Eq, PartialEq)]
-pub struct WsMessage {
-    pub r#type: String,
-    pub payload: HashMap<String, serde_json::Value>,
+const MAX_USERNAME_LENGTH: usize = 24;
+const MAX_CHAT_MESSAGE_LENGTH: usize = 280;
+
+/// Every message a client sends over the socket. The optional `request_id` is
+/// echoed back in the acknowledgement or rejection of that message.
+#[derive(Debug, Clone, JsonSchema)]
+pub struct IncomingMessage {
+    pub request_id: Option<String>,
+    #[serde(flatten)]
+    pub message: ClientMessage,
 }
 
-impl WsMessage {
-    pub fn get_room_code(&self) -> Result<String, Error> {
-        self.payload
-            .get("room_code")
-            .and_then(|v| v.as_str())
-            .map(|s| s.to_string())
-            .ok_or_else(|| Error::GameError("Missing or invalid room_code".to_string()))
-    }
-    pub fn get_player_username(&self) -> Result<String, Error> {
-        self.payload
-            .get("player_username")
-            .and_then(|v| v.as_str())
-            .map(|s| s.to_string())
-            .ok_or_else(|| Error::GameError("Missing or invalid player_username".to_string()))
+#[derive(Debug, Clone, Deserialize, JsonSchema, PartialEq, Eq)]
+#[serde(tag = "type", content = "payload", rename_all = "snake_case")]
+pub enum ClientMessage {
+    Join(JoinPayload),
+    Move(Move),
+    Request(RoomPayload),
+    Leave(RoomPayload),
+    Reset(RoomPayload),
+    Ready(ReadyPayload),
+    Start(RoomPayload),
+    Kick(KickPayload),
+    SetMode(SetModePayload),
+    Lock(LockPayload),
+    Chat(ChatPayload),
+    Mute(MutePayload),
+    Vote(VotePayload),
+    Hint(RoomPayload),
+    WatchLobby(WatchLobbyPayload),
+    QuickMatch(QuickMatchPayload),
+    CancelQuickMatch,
+    Ping,
+}
+
+#[derive(Debug, Clone, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct JoinPayload {
+    pub room_code: String,
+    pub player_username: String,
+    /// Watch the game without taking part in it.
+    #[serde(default)]
+    pub spectator: bool,
+    #[serde(default)]
+    pub passphrase: Option<String>,
+    #[serde(default)]
+    pub invite_code: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct RoomPayload {
+    pub room_code: String,
+}
+
+#[derive(Debug, Clone, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct ReadyPayload {
+    pub room_code: String,
+    pub ready: bool,
+}
+
+#[derive(Debug, Clone, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct KickPayload {
+    pub room_code: String,
+    pub player_id: PlayerId,
+}
+
+#[derive(Debug, Clone, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct SetModePayload {
+    pub room_code: String,
+    pub mode: GameMode,
+    /// Winning score, for race mode.
+    #[serde(default)]
+    pub target_score: Option<i64>,
+}
+
+#[derive(Debug, Clone, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct LockPayload {
+    pub room_code: String,
+    pub locked: bool,
+}
+
+#[derive(Debug, Clone, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct ChatPayload {
+    pub room_code: String,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct MutePayload {
+    pub room_code: String,
+    pub player_id: PlayerId,
+    pub muted: bool,
+}
+
+#[derive(Debug, Clone, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct VotePayload {
+    pub room_code: String,
+    pub vote: VoteKind,
+}
+
+#[derive(Debug, Clone, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct WatchLobbyPayload {
+    pub watching: bool,
+}
+
+#[derive(Debug, Clone, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct QuickMatchPayload {
+    pub mode: GameMode,
+    pub players: usize, // desired match size
+    pub player_username: String,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct InvalidMessage {
+    pub request_id: Option<String>,
+    pub field: String,
+    pub message: String,
+}
+
+#[derive(Deserialize)]
+struct RequestId {
+    #[serde(default)]
+    request_id: Option<String>,
+}
+
+impl IncomingMessage {
+    /// Parses and validates a raw socket message. Errors name the offending field,
+    /// e.g. `payload.cards[0].color`.
+    pub fn parse(text: &str) -> Result<Self, InvalidMessage> {
+        let request_id = serde_json::from_str::<RequestId>(text)
+            .ok()
+            .and_then(|r| r.request_id);
+
+        let deserializer = &mut serde_json::Deserializer::from_str(text);
+        let message: ClientMessage =
+            serde_path_to_error::deserialize(deserializer).map_err(|e| InvalidMessage {
+                request_id: request_id.clone(),
+                field: e.path().to_string(),
+                message: e.inner().to_string(),
+            })?;
+
+        if let Err((field, message)) = message.validate() {
+            return Err(InvalidMessage {
+                request_id,
+                field: field.to_string(),
+                message,
+            });
+        }
+
+        Ok(Self {
+            request_id,
+            message,
+        })
     }
-    pub fn new_update_message(room_code: &str) -> Self {
-        let mut payload = HashMap::new();
-        payload.insert(
-            "room_code".to_string(),
-            serde_json::Value::String(room_code.to_owned()),
-        );
-
-        WsMessage {
-            r#type: "update".to_string(),
-            payload,
+}
+
+impl ClientMessage {
+    fn validate(&self) -> Result<(), (&'static str, String)> {
+        match self {
+            ClientMessage::Join(payload) => {
+                validate_room_code(&payload.room_code)?;
+                validate_username(&payload.player_username)
+            }
+            ClientMessage::Move(game_move) => validate_room_code(&game_move.room_code),
+            ClientMessage::Request(payload)
+            | ClientMessage::Leave(payload)
+            | ClientMessage::Reset(payload)
+            | ClientMessage::Hint(payload)
+            | ClientMessage::Start(payload) => validate_room_code(&payload.room_code),
+            ClientMessage::Ready(payload) => validate_room_code(&payload.room_code),
+            ClientMessage::Kick(payload) => validate_room_code(&payload.room_code),
+            ClientMessage::SetMode(payload) => validate_room_code(&payload.room_code),
+            ClientMessage::Lock(payload) => validate_room_code(&payload.room_code),
+            ClientMessage::Chat(payload) => {
+                validate_room_code(&payload.room_code)?;
+                let message = payload.message.trim();
+                if message.is_empty() {
+                    return Err(("payload.message", "Message must not be empty".to_string()));
+                }
+                if message.chars().count() > MAX_CHAT_MESSAGE_LENGTH {
+                    return Err((
+                        "payload.message",
+                        format!(
+                            "Message must be at most {} characters",
+                            MAX_CHAT_MESSAGE_LENGTH
+                        ),
+                    ));
+                }
+                Ok(())
+            }
+            ClientMessage::Mute(payload) => validate_room_code(&payload.room_code),
+            ClientMessage::Vote(payload) => validate_room_code(&payload.room_code),
+            ClientMessage::QuickMatch(payload) => {
+                if payload.mode == GameMode::Daily {
+                    return Err((
+                        "payload.mode",
+                        "The daily puzzle is played solo".to_string(),
+                    ));
+                }
+                if !(MIN_MATCH_PLAYERS..=MAX_MATCH_PLAYERS).contains(&payload.players) {
+                    return Err((
+                        "payload.players",
+                        format!(
+                            "Matches must have between {} and {} players",
+                            MIN_MATCH_PLAYERS, MAX_MATCH_PLAYERS
+                        ),
+                    ));
+                }
+                validate_username(&payload.player_username)
+            }
+            ClientMessage::WatchLobby(_)
+            | ClientMessage::CancelQuickMatch
+            | ClientMessage::Ping => Ok(()),
         }
     }
-    pub fn get_payload_as<T: DeserializeOwned>(&self) -> Result<T, Error> {
-        let payload_value = serde_json::Value::Object(self.payload.clone().into_iter().collect());
-        serde_json::from_value(payload_value)
-            .map_err(|_| Error::GameError("Failed to parse payload".to_string()))
+}
+
+fn validate_username(player_username: &str) -> Result<(), (&'static str, String)> {
+    let username = player_username.trim();
+    if username.is_empty() {
+        return Err((
+            "payload.player_username",
+            "Username must not be empty".to_string(),
+        ));
+    }
+    if username.chars().count() > MAX_USERNAME_LENGTH {
+        return Err((
+            "payload.player_username",
+            format!(
+                "Username must be at most {} characters",
+                MAX_USERNAME_LENGTH
+            ),
+        ));
     }
+    Ok(())
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
-pub struct JoinPayload {
-    pub room_code: String,
+fn validate_room_code(room_code: &str) -> Result<(), (&'static str, String)> {
+    if room_code.is_empty() || !room_code.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return Err((
+            "payload.room_code",
+            "Room code must be a non-empty alphanumeric string".to_string(),
+        ));
+    }
+    Ok(())
 }
 
-#[derive(Debug)]
-pub enum MessageType {
-    Join(WsMessage),
-    Move(WsMessage),
-    Request(WsMessage),
-    Ping,
-    Leave,
-    Reset(WsMessage),
-}
-
-impl MessageType {
-    pub fn from_ws_message(message: WsMessage) -> Result<Self, Error> {
-        match message.r#type.as_str() {
-            "join" => Ok(MessageType::Join(message)),
-            "move" => Ok(MessageType::Move(message)),
-            "ping" => Ok(MessageType::Ping),
-            "request" => Ok(MessageType::Request(message)),
-            "reset" => Ok(MessageType::Reset(message)),
-
-            _ => Err(Error::GameError(format!(
-                "Unrecognized message type: {}",
-                message.r#type
-            ))),
+/// Every message the server sends over the socket. Cards are written as `C`,
+/// see [`ServerMessage::map_cards`].
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+#[serde(tag = "type", content = "payload", rename_all = "snake_case")]
+pub enum ServerMessage<C = Card> {
+    GameState(Box<GameView<C>>),
+    MoveRejected {
+        request_id: Option<String>,
+        reason: MoveRejection,
+        message: String,
+    },
+    JoinError {
+        request_id: Option<String>,
+        room_code: String,
+        reason: JoinRejection,
+        message: String,
+    },
+    Ack {
+        request_id: String,
+    },
+    Notice {
+        message: String,
+    },
+    Pong {
+        request_id: Option<String>,
+        server_time: u64, // milliseconds since the unix epoch
+    },
+    InvalidMessage(InvalidMessage),
+    Kicked {
+        room_code: String,
+    },
+    Chat(ChatMessage),
+    ChatHistory {
+        room_code: String,
+        messages: Vec<ChatMessage>,
+    },
+    Lobby {
+        rooms: Vec<RoomSummary>,
+    },
+    MatchFound {
+        room_code: String,
+        players: usize,
+    },
+    Hint {
+        room_code: String,
+        cards: Vec<C>, // part of a set that is on the board
+    },
+    Error {
+        request_id: Option<String>,
+        message: String,
+    },
+}
+
+impl<C> ServerMessage<C> {
+    /// The same message with every card passed through `f`.
+    pub fn map_cards<D>(self, mut f: impl FnMut(C) -> D) -> ServerMessage<D> {
+        match self {
+            ServerMessage::GameState(view) => ServerMessage::GameState(Box::new(view.map_cards(f))),
+            ServerMessage::MoveRejected {
+                request_id,
+                reason,
+                message,
+            } => ServerMessage::MoveRejected {
+                request_id,
+                reason,
+                message,
+            },
+            ServerMessage::JoinError {
+                request_id,
+                room_code,
+                reason,
+                message,
+            } => ServerMessage::JoinError {
+                request_id,
+                room_code,
+                reason,
+                message,
+            },
+            ServerMessage::Ack { request_id } => ServerMessage::Ack { request_id },
+            ServerMessage::Notice { message } => ServerMessage::Notice { message },
+            ServerMessage::Pong {
+                request_id,
+                server_time,
+            } => ServerMessage::Pong {
+                request_id,
+                server_time,
+            },
+            ServerMessage::InvalidMessage(invalid) => ServerMessage::InvalidMessage(invalid),
+            ServerMessage::Kicked { room_code } => ServerMessage::Kicked { room_code },
+            ServerMessage::Chat(message) => ServerMessage::Chat(message),
+            ServerMessage::ChatHistory {
+                room_code,
+                messages,
+            } => ServerMessage::ChatHistory {
+                room_code,
+                messages,
+            },
+            ServerMessage::Lobby { rooms } => ServerMessage::Lobby { rooms },
+            ServerMessage::MatchFound { room_code, players } => {
+                ServerMessage::MatchFound { room_code, players }
+            }
+            ServerMessage::Hint { room_code, cards } => ServerMessage::Hint {
+                room_code,
+                cards: cards.into_iter().map(&mut f).collect(),
+            },
+            ServerMessage::Error {
+                request_id,
+                message,
+            } => ServerMessage::Error {
+                request_id,
+                message,
+            },
         }
     }
 }
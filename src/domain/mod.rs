@@ -1,5 +1,10 @@
+pub mod backoff;
+pub mod chat;
 pub mod client;
 pub mod events;
 pub mod game;
+pub mod leaderboard;
+pub mod matchmaking;
 pub mod message;
 pub mod room;
+pub mod vote;
@@ -1,118 +1,945 @@
-use std::sync::Arc;
+use std::{
+    fmt,
+    net::IpAddr,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant, SystemTime},
+};
 
+use ahash::{HashMap, HashMapExt, HashSet, HashSetExt};
 use async_trait::async_trait;
+use serde::Serialize;
 use tokio::sync::Mutex;
 
 use super::{
+    backoff::PassphraseBackoff,
+    chat::{ChatLog, ChatMessage},
     events::CommandResult,
     game::{
         card::Card,
-        game::{Event, Game, GameMode},
-        player::Player,
+        config::{GameConfig, PenaltyPolicy},
+        game::{Event, EventType, Game, GameMode, GameState, Move, MoveRejection},
+        player::{Player, PlayerId},
+        view::GameView,
     },
-    message::WsMessage,
+    leaderboard::{DailyLeaderboardView, DailyResult},
+    message::JoinPayload,
+    vote::{BallotBox, VoteKind, VoteOutcome, VoteThreshold, IDLE_AFTER},
 };
 use crate::infra::error::Error;
 
+pub const MAX_PLAYERS_LIMIT: usize = 32;
+const DEFAULT_MAX_PLAYERS: usize = 8;
+const MAX_PASSPHRASE_LENGTH: usize = 64;
+
+/// Options picked when a room is created.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RoomSettings {
+    pub max_players: usize,
+    pub allow_late_join: bool,
+    pub late_join_score: i64, // starting score for players who join mid-game
+    pub passphrase: Option<String>,
+    pub invite_only: bool, // joining requires the invite code handed out on creation
+    pub public: bool,      // listed in the room browser
+    pub vote_threshold: VoteThreshold,
+}
+
+impl Default for RoomSettings {
+    fn default() -> Self {
+        Self {
+            max_players: DEFAULT_MAX_PLAYERS,
+            allow_late_join: true,
+            late_join_score: 0,
+            passphrase: None,
+            invite_only: false,
+            public: false,
+            vote_threshold: VoteThreshold::default(),
+        }
+    }
+}
+
+impl RoomSettings {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.max_players == 0 || self.max_players > MAX_PLAYERS_LIMIT {
+            return Err(format!(
+                "max_players must be between 1 and {}",
+                MAX_PLAYERS_LIMIT
+            ));
+        }
+        if self.late_join_score < 0 {
+            return Err("late_join_score must not be negative".to_string());
+        }
+        if self.public && self.invite_only {
+            return Err("public rooms cannot be invite-only".to_string());
+        }
+        if let Some(passphrase) = &self.passphrase {
+            if passphrase.is_empty() || passphrase.chars().count() > MAX_PASSPHRASE_LENGTH {
+                return Err(format!(
+                    "passphrase must be between 1 and {} characters",
+                    MAX_PASSPHRASE_LENGTH
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A public room as listed in the room browser.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RoomSummary {
+    pub room_code: String,
+    pub mode: GameMode,
+    pub state: GameState,
+    pub players: usize,
+    pub max_players: usize,
+    pub spectators: usize,
+    pub passphrase_required: bool,
+    pub age_secs: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JoinRejection {
+    RoomNotFound,
+    RoomLocked,
+    Kicked,
+    RoomFull,
+    GameInProgress,
+    GameEnded,
+    PassphraseRequired,
+    WrongPassphrase,
+    TooManyAttempts,
+    InviteRequired,
+}
+
+impl fmt::Display for JoinRejection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let string_representation = match self {
+            JoinRejection::RoomNotFound => "Room not found",
+            JoinRejection::RoomLocked => "The host has locked this room",
+            JoinRejection::Kicked => "You were removed from this room by the host",
+            JoinRejection::RoomFull => "This room is full",
+            JoinRejection::GameInProgress => "This game has already started",
+            JoinRejection::GameEnded => "This game has already ended",
+            JoinRejection::PassphraseRequired => "This room is protected by a passphrase",
+            JoinRejection::WrongPassphrase => "The passphrase is incorrect",
+            JoinRejection::TooManyAttempts => "Too many wrong passphrases, try again later",
+            JoinRejection::InviteRequired => "This room is invite-only",
+        };
+        write!(f, "{}", string_representation)
+    }
+}
+
 pub struct Room {
     game: Arc<Mutex<Game>>,
+    settings: RoomSettings,
+    invite_code: Option<String>,
+    created_at: SystemTime,
+    host: Mutex<Option<u16>>, // client_id of the host
+    locked: AtomicBool,
+    kicked: Mutex<HashSet<u16>>,
+    spectators: Mutex<HashMap<u16, String>>, // client_id -> name
+    chat: Mutex<ChatLog>,
+    votes: Mutex<BallotBox>,
+    activity: Mutex<HashMap<u16, Instant>>, // client_id -> last time the player did something
+    ids: Mutex<HashMap<u16, PlayerId>>,     // client_id -> id shown to other clients
+    passphrase_backoff: Mutex<PassphraseBackoff>,
 }
 
 impl Room {
-    pub fn new(game: Game) -> Self {
+    /// Creates a room hosted by `host`. Without a host, the first player to join
+    /// takes the role. `invite_code` is required to join invite-only rooms.
+    pub fn new(
+        game: Game,
+        settings: RoomSettings,
+        invite_code: Option<String>,
+        host: Option<u16>,
+    ) -> Self {
         Self {
             game: Arc::new(Mutex::new(game)),
+            votes: Mutex::new(BallotBox::new(settings.vote_threshold)),
+            settings,
+            invite_code,
+            created_at: SystemTime::now(),
+            host: Mutex::new(host),
+            locked: AtomicBool::new(false),
+            kicked: Mutex::new(HashSet::new()),
+            spectators: Mutex::new(HashMap::new()),
+            chat: Mutex::new(ChatLog::new()),
+            activity: Mutex::new(HashMap::new()),
+            ids: Mutex::new(HashMap::new()),
+            passphrase_backoff: Mutex::new(PassphraseBackoff::new()),
+        }
+    }
+
+    pub async fn host(&self) -> Option<u16> {
+        *self.host.lock().await
+    }
+
+    pub fn is_public(&self) -> bool {
+        self.settings.public
+    }
+
+    /// The id other clients know this client by. It is drawn on the client's
+    /// first join and kept for the life of the room.
+    async fn player_id(&self, client_id: u16) -> PlayerId {
+        let mut ids = self.ids.lock().await;
+        if let Some(&id) = ids.get(&client_id) {
+            return id;
+        }
+        let id = loop {
+            let id = rand::random::<PlayerId>();
+            if !ids.values().any(|&taken| taken == id) {
+                break id;
+            }
+        };
+        ids.insert(client_id, id);
+        id
+    }
+
+    /// The client behind a player id handed out in this room.
+    pub async fn client_of(&self, id: PlayerId) -> Result<u16, Error> {
+        self.ids
+            .lock()
+            .await
+            .iter()
+            .find(|(_, &player_id)| player_id == id)
+            .map(|(&client_id, _)| client_id)
+            .ok_or_else(|| Error::PlayerNotFound(id.to_string()))
+    }
+
+    /// Fails with [`Error::PermissionDenied`] unless the client hosts this room.
+    pub async fn ensure_host(&self, client_id: u16, action: &str) -> Result<(), Error> {
+        if self.host().await == Some(client_id) {
+            Ok(())
+        } else {
+            Err(Error::PermissionDenied(format!(
+                "Only the host can {}",
+                action
+            )))
+        }
+    }
+
+    /// The room browser entry for this room, if it is public and still open to
+    /// new players.
+    pub async fn public_summary(&self, room_code: &str) -> Option<RoomSummary> {
+        if !self.settings.public || self.locked.load(Ordering::SeqCst) {
+            return None;
+        }
+
+        let game_state = self.game.lock().await;
+        let seats_taken = game_state.players.len() + game_state.disconnected_players.len();
+        let open = match game_state.state {
+            GameState::WaitingForPlayers => true,
+            GameState::Starting | GameState::InProgress => self.settings.allow_late_join,
+            GameState::Ended => false,
+        };
+        if !open || seats_taken >= self.settings.max_players {
+            return None;
+        }
+
+        Some(RoomSummary {
+            room_code: room_code.to_string(),
+            mode: game_state.mode.clone(),
+            state: game_state.state.clone(),
+            players: game_state.players.len(),
+            max_players: self.settings.max_players,
+            spectators: self.spectators.lock().await.len(),
+            passphrase_required: self.settings.passphrase.is_some(),
+            age_secs: self.created_at.elapsed().unwrap_or_default().as_secs(),
+        })
+    }
+
+    /// Checks the passphrase or invite code of a join request. The host and
+    /// players who already have a seat are let through. `peer` is the address
+    /// the request came from, if known.
+    pub async fn check_access(
+        &self,
+        client_id: u16,
+        peer: Option<IpAddr>,
+        passphrase: Option<&str>,
+        invite_code: Option<&str>,
+    ) -> Result<(), JoinRejection> {
+        if self.host().await == Some(client_id) || self.game.lock().await.has_player(client_id) {
+            return Ok(());
+        }
+
+        if let Some(expected) = &self.settings.passphrase {
+            let Some(passphrase) = passphrase else {
+                return Err(JoinRejection::PassphraseRequired);
+            };
+            self.check_passphrase(peer, passphrase, expected).await?;
+        }
+
+        if self.settings.invite_only {
+            let invited = match (invite_code, &self.invite_code) {
+                (Some(given), Some(expected)) => secrets_match(given, expected),
+                _ => false,
+            };
+            if !invited {
+                return Err(JoinRejection::InviteRequired);
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks a passphrase, making peers that keep guessing wrong wait longer
+    /// and longer between tries.
+    async fn check_passphrase(
+        &self,
+        peer: Option<IpAddr>,
+        passphrase: &str,
+        expected: &str,
+    ) -> Result<(), JoinRejection> {
+        let mut backoff = self.passphrase_backoff.lock().await;
+        let now = Instant::now();
+        if !backoff.may_guess(peer, now) {
+            return Err(JoinRejection::TooManyAttempts);
+        }
+
+        if secrets_match(passphrase, expected) {
+            backoff.record_success(peer);
+            Ok(())
+        } else {
+            backoff.record_failure(peer, now);
+            Err(JoinRejection::WrongPassphrase)
         }
     }
 
-    pub async fn reset_game(&self) -> Result<(), Error> {
+    /// Kicks the player or spectator with the given id and returns their
+    /// client_id.
+    pub async fn kick_player(&self, client_id: u16, target: PlayerId) -> Result<u16, Error> {
+        self.ensure_host(client_id, "kick players").await?;
+        let target_id = self.client_of(target).await?;
+        if target_id == client_id {
+            return Err(Error::GameRuleError(
+                "The host cannot kick themselves".to_string(),
+            ));
+        }
+
         let mut game_state = self.game.lock().await;
-        game_state.reset();
+        self.expel(&mut game_state, target_id).await?;
+        Ok(target_id)
+    }
+
+    /// Removes a player or spectator from the room for good.
+    async fn expel(&self, game_state: &mut Game, target_id: u16) -> Result<(), Error> {
+        let player_name = game_state
+            .players
+            .iter()
+            .find(|p| p.client_id == target_id)
+            .map(|p| p.name.clone());
+        let was_spectator = self.spectators.lock().await.remove(&target_id).is_some();
+        if !game_state.leave_player(target_id) && !was_spectator {
+            return Err(Error::PlayerNotFound(target_id.to_string()));
+        }
+        if let Some(player_name) = player_name {
+            game_state
+                .events
+                .push(Event::new(EventType::PlayerKicked, player_name));
+        }
+
+        self.kicked.lock().await.insert(target_id);
+        Ok(())
+    }
+
+    pub async fn set_mode(
+        &self,
+        client_id: u16,
+        mode: GameMode,
+        target_score: Option<i64>,
+    ) -> Result<(), Error> {
+        self.ensure_host(client_id, "change the game mode").await?;
+        let mut game_state = self.game.lock().await;
+        Self::change_mode(&mut game_state, mode, target_score)
+    }
+
+    fn change_mode(
+        game_state: &mut Game,
+        mode: GameMode,
+        target_score: Option<i64>,
+    ) -> Result<(), Error> {
+        let config = GameConfig::for_mode(&mode, target_score).map_err(Error::GameRuleError)?;
+        // Only the target changes with the mode; the other rules stay as picked.
+        let config = GameConfig {
+            target_score: config.target_score,
+            ..game_state.config.clone()
+        };
+        game_state.set_mode(mode, config)
+    }
+
+    pub async fn hint(&self, client_id: u16) -> Result<Vec<Card>, Error> {
+        self.touch(client_id).await;
+        self.game.lock().await.hint(client_id)
+    }
+
+    pub async fn penalty(&self) -> PenaltyPolicy {
+        self.game.lock().await.config.penalty
+    }
+
+    pub async fn set_locked(&self, client_id: u16, locked: bool) -> Result<(), Error> {
+        self.ensure_host(client_id, "lock the room").await?;
+        self.locked.store(locked, Ordering::SeqCst);
         Ok(())
     }
 
-    pub async fn handle_move(&self, client_id: u16, cards: &[Card]) -> Result<bool, Error> {
+    /// Mutes or unmutes the player or spectator with the given id and returns
+    /// their client_id.
+    pub async fn set_muted(
+        &self,
+        client_id: u16,
+        target: PlayerId,
+        muted: bool,
+    ) -> Result<u16, Error> {
+        self.ensure_host(client_id, "mute players").await?;
+        let target_id = self.client_of(target).await?;
+        self.chat.lock().await.set_muted(target_id, muted);
+        Ok(target_id)
+    }
+
+    /// Posts a chat message from a player or spectator in this room.
+    pub async fn post_chat_message(
+        &self,
+        client_id: u16,
+        message: String,
+    ) -> Result<ChatMessage, Error> {
+        self.touch(client_id).await;
+        let player_name = self
+            .game
+            .lock()
+            .await
+            .players
+            .iter()
+            .find(|p| p.client_id == client_id)
+            .map(|p| p.name.clone());
+        let name = match player_name {
+            Some(name) => name,
+            None => self
+                .spectators
+                .lock()
+                .await
+                .get(&client_id)
+                .cloned()
+                .ok_or_else(|| Error::PlayerNotFound(client_id.to_string()))?,
+        };
+
+        let player_id = self.player_id(client_id).await;
+        self.chat
+            .lock()
+            .await
+            .post(client_id, player_id, name, message)
+    }
+
+    pub async fn chat_history(&self) -> Vec<ChatMessage> {
+        self.chat.lock().await.history()
+    }
+
+    /// Hands the host role to the longest-connected player once the current host
+    /// is gone for good. Returns the new host, if the role changed hands.
+    pub async fn transfer_host_if_needed(&self) -> Option<u16> {
         let mut game_state = self.game.lock().await;
-        let result = game_state.make_move(client_id, cards)?;
-        if result {
-            Ok(true)
+        let mut host = self.host.lock().await;
+
+        if host.is_some_and(|host| game_state.has_player(host)) {
+            return None;
+        }
+
+        // Players are kept in join order, so the first one has been here longest.
+        let (next_host, player_name) = match game_state.players.first() {
+            Some(player) => (Some(player.client_id), player.name.clone()),
+            None => (None, String::new()),
+        };
+        if next_host == *host {
+            return None;
+        }
+
+        *host = next_host;
+        if next_host.is_some() {
+            game_state
+                .events
+                .push(Event::new(EventType::HostChanged, player_name));
+        }
+        next_host
+    }
+
+    /// Drops players whose reconnection window has expired and moves the host
+    /// role on if the host was one of them.
+    pub async fn expire_disconnected_players(&self) -> Option<u16> {
+        let purged = self.game.lock().await.purge_expired_players();
+        if purged {
+            self.transfer_host_if_needed().await
         } else {
-            Ok(false)
+            None
         }
     }
 
+    pub async fn reset_game(&self, client_id: u16) -> Result<(), Error> {
+        self.ensure_host(client_id, "reset the game").await?;
+        let mut game_state = self.game.lock().await;
+        let player_name = game_state
+            .players
+            .iter()
+            .find(|p| p.client_id == client_id)
+            .map(|p| p.name.clone())
+            .ok_or_else(|| Error::PlayerNotFound(client_id.to_string()))?;
+
+        game_state.reset();
+        game_state
+            .events
+            .push(Event::new(EventType::GameReset, player_name));
+        self.votes.lock().await.clear();
+        Ok(())
+    }
+
+    pub async fn handle_move(&self, client_id: u16, cards: &[Card]) -> Result<(), MoveRejection> {
+        self.touch(client_id).await;
+        let mut game_state = self.game.lock().await;
+        game_state.make_move(client_id, cards)
+    }
+
+    pub async fn is_spectator(&self, client_id: u16) -> bool {
+        self.spectators.lock().await.contains_key(&client_id)
+    }
+
+    pub async fn is_player(&self, client_id: u16) -> bool {
+        let game_state = self.game.lock().await;
+        game_state.players.iter().any(|p| p.client_id == client_id)
+    }
+
+    /// Returns `true` once every player in the lobby is ready.
+    pub async fn set_player_ready(&self, client_id: u16, ready: bool) -> Result<bool, Error> {
+        self.touch(client_id).await;
+        let mut game_state = self.game.lock().await;
+        game_state.set_ready(client_id, ready)
+    }
+
+    pub async fn begin_countdown(&self, countdown: Duration) -> Option<u64> {
+        let mut game_state = self.game.lock().await;
+        let countdown_ends_at = game_state.begin_countdown(countdown)?;
+
+        // Everyone starts the game active, however long they waited in the lobby.
+        let now = Instant::now();
+        let mut activity = self.activity.lock().await;
+        for player in &game_state.players {
+            activity.insert(player.client_id, now);
+        }
+        Some(countdown_ends_at)
+    }
+
+    pub async fn start_game(&self, countdown_ends_at: u64) -> bool {
+        let mut game_state = self.game.lock().await;
+        game_state.start(countdown_ends_at)
+    }
+
     pub async fn is_game_over(&self) -> Result<bool, Error> {
         let game_state = self.game.lock().await;
         Ok(game_state.game_over.is_some())
     }
 
+    /// The leaderboard entry for a solved daily puzzle, as the day of the
+    /// puzzle and the result. Only solo solves count.
+    pub async fn daily_result(&self, client_id: u16) -> Option<(u64, DailyResult)> {
+        let game_state = self.game.lock().await;
+        let puzzle = game_state.puzzle.as_ref()?;
+        let [player] = game_state.players.as_slice() else {
+            return None;
+        };
+        if player.client_id != client_id {
+            return None;
+        }
+
+        let result = DailyResult {
+            client_id,
+            name: player.name.clone(),
+            time_ms: game_state.solve_time()?,
+        };
+        Some((puzzle.day, result))
+    }
+
     pub async fn remove_player(&self, client_id: u16) -> Result<(), Error> {
+        if self.spectators.lock().await.remove(&client_id).is_some() {
+            return Ok(());
+        }
+
         let mut game_state = self.game.lock().await;
         game_state.remove_player(client_id);
         Ok(())
     }
 
+    pub async fn leave_player(&self, client_id: u16) -> Result<(), Error> {
+        if self.spectators.lock().await.remove(&client_id).is_some() {
+            return Ok(());
+        }
+
+        let left = self.game.lock().await.leave_player(client_id);
+        if !left {
+            return Err(Error::PlayerNotFound(client_id.to_string()));
+        }
+        self.transfer_host_if_needed().await;
+        Ok(())
+    }
+
     pub async fn get_game_state(&self) -> Arc<Mutex<Game>> {
         self.game.clone()
     }
 
-    pub async fn join_player(&self, client_id: u16, player_username: String) -> Result<(), Error> {
+    pub async fn get_game_view(&self) -> GameView {
+        let game_state = self.game.lock().await;
+        let active_players = self.active_players(&game_state).await.len();
+        let ids = self.ids.lock().await;
+        GameView {
+            votes: self.votes.lock().await.views(active_players, &ids),
+            vote_threshold: self.settings.vote_threshold,
+            host: self.host().await.and_then(|host| ids.get(&host).copied()),
+            locked: self.locked.load(Ordering::SeqCst),
+            spectators: self.spectators.lock().await.len(),
+            max_players: self.settings.max_players,
+            ..GameView::from(&*game_state)
+        }
+    }
+
+    /// Adds the client to the game, or restores their previous player if they are
+    /// rejoining within the reconnection window. Returns `true` on a rejoin.
+    /// Players already seated may always come back, even into a locked room.
+    /// Spectators only receive the game state; they never get a [`Player`].
+    pub async fn join_player(
+        &self,
+        client_id: u16,
+        player_username: String,
+        spectator: bool,
+    ) -> Result<bool, JoinRejection> {
         let mut game_state = self.game.lock().await;
+        if !spectator {
+            self.touch(client_id).await;
+        }
 
-        if game_state.restore_player(client_id).is_err() {
-            let player = Player::new(client_id, player_username);
-            game_state.add_player(player);
+        if game_state.restore_player(client_id).is_ok() {
+            return Ok(true);
+        }
+        if game_state.players.iter().any(|p| p.client_id == client_id) {
+            return Ok(true);
         }
 
-        Ok(())
+        if self.kicked.lock().await.contains(&client_id) {
+            return Err(JoinRejection::Kicked);
+        }
+        if self.locked.load(Ordering::SeqCst) {
+            return Err(JoinRejection::RoomLocked);
+        }
+        if game_state.state == GameState::Ended {
+            return Err(JoinRejection::GameEnded);
+        }
+
+        let player_id = self.player_id(client_id).await;
+        if spectator {
+            self.spectators
+                .lock()
+                .await
+                .insert(client_id, player_username);
+            return Ok(false);
+        }
+        let in_progress = game_state.state != GameState::WaitingForPlayers;
+        if in_progress && !self.settings.allow_late_join {
+            return Err(JoinRejection::GameInProgress);
+        }
+        // Seats of disconnected players stay reserved until their window runs out.
+        let seats_taken = game_state.players.len() + game_state.disconnected_players.len();
+        if seats_taken >= self.settings.max_players {
+            return Err(JoinRejection::RoomFull);
+        }
+        self.spectators.lock().await.remove(&client_id);
+
+        let mut player = Player::new(client_id, player_id, player_username);
+        if in_progress {
+            player.score = self.settings.late_join_score;
+        }
+        game_state.add_player(player);
+
+        let mut host = self.host.lock().await;
+        if host.is_none() {
+            *host = Some(client_id);
+        }
+        Ok(false)
     }
 
     pub async fn request_cards(&self, client_id: u16) -> Result<(), Error> {
-        let mut game_state = self.game.lock().await;
+        if self.is_spectator(client_id).await {
+            return Err(Error::GameRuleError(
+                "Spectators cannot request cards".to_string(),
+            ));
+        }
 
-        if let Some(player) = game_state
+        self.vote(client_id, VoteKind::AddCards).await?;
+        Ok(())
+    }
+
+    /// Records that the client just did something in the room.
+    async fn touch(&self, client_id: u16) {
+        self.activity.lock().await.insert(client_id, Instant::now());
+    }
+
+    /// Players who have done something within [`IDLE_AFTER`].
+    async fn active_players(&self, game_state: &Game) -> HashSet<u16> {
+        let activity = self.activity.lock().await;
+        game_state
+            .players
+            .iter()
+            .map(|p| p.client_id)
+            .filter(|client_id| {
+                activity
+                    .get(client_id)
+                    .is_some_and(|at| at.elapsed() < IDLE_AFTER)
+            })
+            .collect()
+    }
+
+    /// Casts a player's vote and carries out the decision once enough of the
+    /// active players agree. Idle and disconnected players are left out of the
+    /// quorum, as is the target of a kick.
+    pub async fn vote(&self, client_id: u16, kind: VoteKind) -> Result<VoteOutcome, Error> {
+        self.touch(client_id).await;
+        let mut game_state = self.game.lock().await;
+        let player_name = game_state
             .players
-            .iter_mut()
+            .iter()
             .find(|p| p.client_id == client_id)
-        {
-            player.request = true;
-            let player_name = player.name.clone();
+            .map(|p| p.name.clone())
+            .ok_or_else(|| Error::PermissionDenied("Only players can vote".to_string()))?;
+
+        match &kind {
+            VoteKind::AddCards => {
+                if game_state.state != GameState::InProgress {
+                    return Err(Error::GameRuleError(
+                        "The game has not started yet".to_string(),
+                    ));
+                }
+                if game_state.puzzle.is_some() {
+                    return Err(Error::GameRuleError(
+                        "No cards are added to a daily board".to_string(),
+                    ));
+                }
+            }
+            VoteKind::Rematch => {
+                if game_state.state != GameState::Ended {
+                    return Err(Error::GameRuleError(
+                        "A rematch can only be started once the game is over".to_string(),
+                    ));
+                }
+            }
+            VoteKind::Kick { player_id } => {
+                let target_id = self.client_of(*player_id).await?;
+                if target_id == client_id {
+                    return Err(Error::GameRuleError(
+                        "You cannot vote to kick yourself".to_string(),
+                    ));
+                }
+                if !game_state.has_player(target_id) && !self.is_spectator(target_id).await {
+                    return Err(Error::PlayerNotFound(player_id.to_string()));
+                }
+                if self.host().await == Some(target_id)
+                    && self.settings.vote_threshold != VoteThreshold::HostOverride
+                {
+                    return Err(Error::PermissionDenied(
+                        "The host can only be voted out in rooms where the host can override votes"
+                            .to_string(),
+                    ));
+                }
+            }
+            VoteKind::ChangeMode { mode, target_score } => {
+                if game_state.state != GameState::WaitingForPlayers {
+                    return Err(Error::GameRuleError(
+                        "The mode can only be changed before the game starts".to_string(),
+                    ));
+                }
+                GameConfig::for_mode(mode, *target_score).map_err(Error::GameRuleError)?;
+            }
+        }
+
+        let mut eligible = self.active_players(&game_state).await;
+        eligible.insert(client_id);
+        if let VoteKind::Kick { player_id } = kind {
+            eligible.remove(&self.client_of(player_id).await?);
+        }
+        let is_host = self.host().await == Some(client_id);
+
+        let mut votes = self.votes.lock().await;
+        Self::close_expired_votes(&mut game_state, &mut votes);
+        let outcome = votes.cast(kind.clone(), client_id, &eligible, is_host);
+
+        if kind == VoteKind::AddCards {
+            if let Some(player) = game_state
+                .players
+                .iter_mut()
+                .find(|p| p.client_id == client_id)
+            {
+                player.request = true;
+            }
+            game_state.events.push(Event::new(
+                EventType::PlayerRequestedCards,
+                player_name.clone(),
+            ));
+        } else {
             game_state.events.push(Event::new(
-                super::game::game::EventType::PlayerRequestedCards,
-                player_name,
+                EventType::VoteCast,
+                format!("{} voted to {}", player_name, kind),
             ));
+        }
+
+        let VoteOutcome::Passed(passed) = &outcome else {
+            return Ok(outcome);
+        };
+        game_state
+            .events
+            .push(Event::new(EventType::VotePassed, passed.to_string()));
 
-            let all_requested = game_state.players.iter().all(|player| player.request);
-            if all_requested && !game_state.deck.cards.is_empty() {
+        match passed.clone() {
+            VoteKind::AddCards => {
                 game_state.add_cards();
                 for player in game_state.players.iter_mut() {
                     player.request = false; // Reset the request flags
                 }
+                game_state.end_if_exhausted();
+            }
+            VoteKind::Rematch => {
+                game_state.reset();
+                game_state
+                    .events
+                    .push(Event::new(EventType::GameReset, player_name));
+                votes.clear();
+            }
+            VoteKind::Kick { player_id } => {
+                let target_id = self.client_of(player_id).await?;
+                self.expel(&mut game_state, target_id).await?;
+                drop(votes);
+                drop(game_state);
+                self.transfer_host_if_needed().await;
+            }
+            VoteKind::ChangeMode { mode, target_score } => {
+                Self::change_mode(&mut game_state, mode, target_score)?;
+            }
+        }
+        Ok(outcome)
+    }
+
+    /// Closes votes that ran out of time. Returns `true` if any did.
+    pub async fn expire_votes(&self) -> bool {
+        let mut game_state = self.game.lock().await;
+        let mut votes = self.votes.lock().await;
+        Self::close_expired_votes(&mut game_state, &mut votes)
+    }
+
+    fn close_expired_votes(game_state: &mut Game, votes: &mut BallotBox) -> bool {
+        let expired = votes.expire(Instant::now());
+        if expired.contains(&VoteKind::AddCards) {
+            for player in game_state.players.iter_mut() {
+                player.request = false;
             }
-            Ok(())
-        } else {
-            Err(Error::PlayerNotFound(client_id.to_string()))
         }
+        for kind in &expired {
+            game_state
+                .events
+                .push(Event::new(EventType::VoteExpired, kind.to_string()));
+        }
+        !expired.is_empty()
     }
 }
 
 #[async_trait]
 pub trait RoomServiceTrait {
-    async fn handle_join(&self, message: WsMessage, client_id: u16)
-        -> Result<CommandResult, Error>;
+    async fn handle_join(
+        &self,
+        payload: JoinPayload,
+        client_id: u16,
+        peer: Option<IpAddr>,
+    ) -> Result<CommandResult, Error>;
     async fn handle_player_move(
         &self,
         client_id: u16,
-        message: WsMessage,
+        game_move: Move,
     ) -> Result<CommandResult, Error>;
     async fn get_room(&self, room_code: &str) -> Result<Arc<Room>, Error>;
     async fn handle_request_cards(
         &self,
         client_id: u16,
-        message: WsMessage,
+        room_code: String,
     ) -> Result<CommandResult, Error>;
     async fn handle_leave(&self, client_id: u16, room_code: String)
         -> Result<CommandResult, Error>;
-    async fn start_new_game(&self, mode: GameMode) -> Result<CommandResult, Error>;
+    async fn handle_leave_room(
+        &self,
+        client_id: u16,
+        room_code: String,
+    ) -> Result<CommandResult, Error>;
+    async fn handle_reset(&self, client_id: u16, room_code: String)
+        -> Result<CommandResult, Error>;
+    async fn handle_ready(
+        &self,
+        client_id: u16,
+        room_code: String,
+        ready: bool,
+    ) -> Result<CommandResult, Error>;
+    async fn handle_start(&self, client_id: u16, room_code: String)
+        -> Result<CommandResult, Error>;
+    async fn handle_kick(
+        &self,
+        client_id: u16,
+        room_code: String,
+        target: PlayerId,
+    ) -> Result<CommandResult, Error>;
+    async fn handle_set_mode(
+        &self,
+        client_id: u16,
+        room_code: String,
+        mode: GameMode,
+        target_score: Option<i64>,
+    ) -> Result<CommandResult, Error>;
+    async fn handle_lock(
+        &self,
+        client_id: u16,
+        room_code: String,
+        locked: bool,
+    ) -> Result<CommandResult, Error>;
+    async fn handle_chat(
+        &self,
+        client_id: u16,
+        room_code: String,
+        message: String,
+    ) -> Result<CommandResult, Error>;
+    async fn handle_mute(
+        &self,
+        client_id: u16,
+        room_code: String,
+        target: PlayerId,
+        muted: bool,
+    ) -> Result<CommandResult, Error>;
+    async fn handle_vote(
+        &self,
+        client_id: u16,
+        room_code: String,
+        kind: VoteKind,
+    ) -> Result<CommandResult, Error>;
+    async fn handle_hint(&self, client_id: u16, room_code: String) -> Result<CommandResult, Error>;
+    async fn start_new_game(
+        &self,
+        mode: GameMode,
+        config: GameConfig,
+        settings: RoomSettings,
+        host: Option<u16>,
+    ) -> Result<CommandResult, Error>;
+    async fn list_public_rooms(&self) -> Vec<RoomSummary>;
+    async fn daily_leaderboard(&self, day: u64) -> DailyLeaderboardView;
     async fn broadcast_game_state(&self, room_code: String) -> Result<(), Error>;
 }
+
+/// Compares two secrets in time that depends only on their lengths, so a wrong
+/// guess does not reveal how much of it was right.
+fn secrets_match(given: &str, expected: &str) -> bool {
+    given.len() == expected.len()
+        && given
+            .bytes()
+            .zip(expected.bytes())
+            .fold(0, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
@@ -0,0 +1,332 @@
+use std::{
+    fmt,
+    str::FromStr,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use ahash::{HashMap, HashSet, HashSetExt};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::game::{game::GameMode, player::PlayerId};
+
+pub const VOTE_TIMEOUT: Duration = Duration::from_secs(30);
+/// Players who have not done anything for this long don't count towards the
+/// quorum, so an idle player can't hold a vote up.
+pub const IDLE_AFTER: Duration = Duration::from_secs(120);
+
+/// A room decision that players can vote on.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum VoteKind {
+    AddCards,
+    Rematch,
+    Kick {
+        player_id: PlayerId,
+    },
+    ChangeMode {
+        mode: GameMode,
+        #[serde(default)]
+        target_score: Option<i64>,
+    },
+}
+
+impl fmt::Display for VoteKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VoteKind::AddCards => write!(f, "add cards"),
+            VoteKind::Rematch => write!(f, "play again"),
+            VoteKind::Kick { player_id } => write!(f, "kick player {}", player_id),
+            VoteKind::ChangeMode { mode, .. } => write!(f, "switch to {}", mode),
+        }
+    }
+}
+
+impl VoteKind {
+    /// The fewest yes votes that pass this vote, however few players are active.
+    /// Kicking takes two, so one player can never remove another on their own.
+    pub fn min_votes(&self) -> usize {
+        match self {
+            VoteKind::Kick { .. } => 2,
+            _ => 1,
+        }
+    }
+}
+
+/// How many of the active players have to agree before a vote passes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VoteThreshold {
+    #[default]
+    Majority,
+    AllActive,
+    HostOverride, // a majority, or the host on their own
+}
+
+impl FromStr for VoteThreshold {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "majority" => Ok(VoteThreshold::Majority),
+            "all" => Ok(VoteThreshold::AllActive),
+            "host" => Ok(VoteThreshold::HostOverride),
+            _ => Err("vote must be one of majority, all or host"),
+        }
+    }
+}
+
+impl VoteThreshold {
+    /// Yes votes needed out of `eligible` active voters.
+    pub fn needed(&self, eligible: usize) -> usize {
+        match self {
+            VoteThreshold::Majority | VoteThreshold::HostOverride => eligible / 2 + 1,
+            VoteThreshold::AllActive => eligible.max(1),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VoteOutcome {
+    Pending,
+    Passed(VoteKind),
+}
+
+#[derive(Debug, Clone)]
+struct Vote {
+    kind: VoteKind,
+    yes: HashSet<u16>,
+    started_at: Instant,
+    expires_at: u64, // milliseconds since the unix epoch
+}
+
+/// An open vote as shown to clients.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct VoteView {
+    pub kind: VoteKind,
+    pub voters: Vec<PlayerId>,
+    pub needed: usize,
+    pub expires_at: u64, // milliseconds since the unix epoch
+}
+
+/// The open votes of a room. At most one vote per decision runs at a time;
+/// voting for a decision that is already open adds to that vote.
+#[derive(Debug)]
+pub struct BallotBox {
+    threshold: VoteThreshold,
+    votes: Vec<Vote>,
+}
+
+impl BallotBox {
+    pub fn new(threshold: VoteThreshold) -> Self {
+        Self {
+            threshold,
+            votes: vec![],
+        }
+    }
+
+    /// Records a yes vote and reports whether the decision passed. Only votes
+    /// from `eligible` clients are counted; a passed vote is closed.
+    pub fn cast(
+        &mut self,
+        kind: VoteKind,
+        client_id: u16,
+        eligible: &HashSet<u16>,
+        is_host: bool,
+    ) -> VoteOutcome {
+        let index = match self.votes.iter().position(|v| v.kind == kind) {
+            Some(index) => index,
+            None => {
+                let expires_at = SystemTime::now() + VOTE_TIMEOUT;
+                self.votes.push(Vote {
+                    kind: kind.clone(),
+                    yes: HashSet::new(),
+                    started_at: Instant::now(),
+                    expires_at: expires_at
+                        .duration_since(UNIX_EPOCH)
+                        .expect("Time went backwards")
+                        .as_millis() as u64,
+                });
+                self.votes.len() - 1
+            }
+        };
+
+        let vote = &mut self.votes[index];
+        vote.yes.insert(client_id);
+        let counted = vote.yes.iter().filter(|id| eligible.contains(id)).count();
+        let host_override = is_host && self.threshold == VoteThreshold::HostOverride;
+
+        let needed = self
+            .threshold
+            .needed(eligible.len())
+            .max(vote.kind.min_votes());
+        if host_override || counted >= needed {
+            self.votes.remove(index);
+            VoteOutcome::Passed(kind)
+        } else {
+            VoteOutcome::Pending
+        }
+    }
+
+    /// Closes every vote older than [`VOTE_TIMEOUT`] and returns what they were
+    /// about.
+    pub fn expire(&mut self, now: Instant) -> Vec<VoteKind> {
+        let (expired, open): (Vec<Vote>, Vec<Vote>) = self
+            .votes
+            .drain(..)
+            .partition(|v| now.duration_since(v.started_at) >= VOTE_TIMEOUT);
+        self.votes = open;
+        expired.into_iter().map(|v| v.kind).collect()
+    }
+
+    /// Drops every open vote, e.g. when the game is reset.
+    pub fn clear(&mut self) {
+        self.votes.clear();
+    }
+
+    /// The open votes as shown to clients, with voters given by the ids in
+    /// `ids` (client_id -> player id).
+    pub fn views(&self, eligible: usize, ids: &HashMap<u16, PlayerId>) -> Vec<VoteView> {
+        self.votes
+            .iter()
+            .map(|v| VoteView {
+                kind: v.kind.clone(),
+                voters: v.yes.iter().filter_map(|id| ids.get(id).copied()).collect(),
+                needed: self.threshold.needed(eligible).max(v.kind.min_votes()),
+                expires_at: v.expires_at,
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eligible(ids: &[u16]) -> HashSet<u16> {
+        ids.iter().copied().collect()
+    }
+
+    #[test]
+    fn a_majority_passes_a_vote() {
+        let mut ballots = BallotBox::new(VoteThreshold::Majority);
+        let players = eligible(&[1, 2, 3, 4]);
+        for id in [1, 2] {
+            assert_eq!(
+                ballots.cast(VoteKind::AddCards, id, &players, false),
+                VoteOutcome::Pending
+            );
+        }
+        assert_eq!(
+            ballots.cast(VoteKind::AddCards, 3, &players, false),
+            VoteOutcome::Passed(VoteKind::AddCards)
+        );
+        assert!(ballots.views(4, &HashMap::default()).is_empty());
+    }
+
+    #[test]
+    fn voting_twice_counts_once() {
+        let mut ballots = BallotBox::new(VoteThreshold::Majority);
+        let players = eligible(&[1, 2, 3]);
+        for _ in 0..3 {
+            assert_eq!(
+                ballots.cast(VoteKind::Rematch, 1, &players, false),
+                VoteOutcome::Pending
+            );
+        }
+    }
+
+    #[test]
+    fn only_eligible_votes_count() {
+        let mut ballots = BallotBox::new(VoteThreshold::Majority);
+        let players = eligible(&[1, 2, 3]);
+        ballots.cast(VoteKind::AddCards, 1, &players, false);
+        assert_eq!(
+            ballots.cast(VoteKind::AddCards, 9, &players, false),
+            VoteOutcome::Pending
+        );
+        assert_eq!(
+            ballots.cast(VoteKind::AddCards, 2, &players, false),
+            VoteOutcome::Passed(VoteKind::AddCards)
+        );
+    }
+
+    #[test]
+    fn all_active_needs_every_eligible_player() {
+        let mut ballots = BallotBox::new(VoteThreshold::AllActive);
+        let players = eligible(&[1, 2, 3]);
+        for id in [1, 2] {
+            assert_eq!(
+                ballots.cast(VoteKind::Rematch, id, &players, true),
+                VoteOutcome::Pending
+            );
+        }
+        assert_eq!(
+            ballots.cast(VoteKind::Rematch, 3, &players, false),
+            VoteOutcome::Passed(VoteKind::Rematch)
+        );
+    }
+
+    #[test]
+    fn the_host_alone_passes_a_host_override_vote() {
+        let mut ballots = BallotBox::new(VoteThreshold::HostOverride);
+        let players = eligible(&[1, 2, 3, 4]);
+        assert_eq!(
+            ballots.cast(VoteKind::AddCards, 2, &players, false),
+            VoteOutcome::Pending
+        );
+        assert_eq!(
+            ballots.cast(VoteKind::AddCards, 1, &players, true),
+            VoteOutcome::Passed(VoteKind::AddCards)
+        );
+    }
+
+    #[test]
+    fn kicking_takes_two_votes_however_few_are_eligible() {
+        let kick = VoteKind::Kick { player_id: 7 };
+        let mut ballots = BallotBox::new(VoteThreshold::Majority);
+        let players = eligible(&[1]);
+        assert_eq!(
+            ballots.cast(kick.clone(), 1, &players, false),
+            VoteOutcome::Pending
+        );
+        let players = eligible(&[1, 2]);
+        assert_eq!(
+            ballots.cast(kick.clone(), 2, &players, false),
+            VoteOutcome::Passed(kick)
+        );
+    }
+
+    #[test]
+    fn votes_on_different_decisions_are_counted_apart() {
+        let mut ballots = BallotBox::new(VoteThreshold::Majority);
+        let players = eligible(&[1, 2, 3]);
+        ballots.cast(VoteKind::AddCards, 1, &players, false);
+        assert_eq!(
+            ballots.cast(VoteKind::Rematch, 2, &players, false),
+            VoteOutcome::Pending
+        );
+        assert_eq!(ballots.views(3, &HashMap::default()).len(), 2);
+    }
+
+    #[test]
+    fn votes_expire_after_the_timeout() {
+        let mut ballots = BallotBox::new(VoteThreshold::Majority);
+        let players = eligible(&[1, 2, 3]);
+        let started = Instant::now();
+        ballots.cast(VoteKind::AddCards, 1, &players, false);
+
+        assert!(ballots.expire(started).is_empty());
+        assert_eq!(
+            ballots.expire(started + VOTE_TIMEOUT + Duration::from_millis(1)),
+            vec![VoteKind::AddCards]
+        );
+        assert!(ballots.views(3, &HashMap::default()).is_empty());
+
+        // A vote cast after expiry starts over.
+        assert_eq!(
+            ballots.cast(VoteKind::AddCards, 2, &players, false),
+            VoteOutcome::Pending
+        );
+    }
+}
@@ -37,6 +37,12 @@ pub enum Error {
     #[error("Game rules error. {0}")]
     GameRuleError(String),
 
+    #[error("Permission denied. {0}")]
+    PermissionDenied(String),
+
+    #[error("Rate limited. {0}")]
+    RateLimited(String),
+
     #[error("Database error: {0}")]
     DatabaseError(String),
 
@@ -56,16 +62,34 @@ pub enum Error {
     ClientIdMissing,
 }
 
+impl Error {
+    /// What the client behind a failed command is told. Rule violations carry
+    /// a message written for players; anything else is summed up, as it may name
+    /// other clients or server internals.
+    pub fn client_message(&self) -> String {
+        match self {
+            Error::GameRuleError(message)
+            | Error::PermissionDenied(message)
+            | Error::RateLimited(message)
+            | Error::RoomNotFound(message)
+            | Error::ClientNotFound(message) => message.clone(),
+            Error::PlayerNotFound(_) => "Player not found".to_string(),
+            Error::UnknownMove(_) => "Unknown move".to_string(),
+            _ => "Something went wrong".to_string(),
+        }
+    }
+}
+
 pub struct AppError(pub Error);
 
 // Tell axum how to convert `AppError` into a response.
 impl IntoResponse for AppError {
     fn into_response(self) -> Response {
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            format!("Something went wrong: {}", self.0),
-        )
-            .into_response()
+        let status = match self.0 {
+            Error::ClientIdMissing => StatusCode::UNAUTHORIZED,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        (status, format!("Something went wrong: {}", self.0)).into_response()
     }
 }
 
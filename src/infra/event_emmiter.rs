@@ -122,12 +122,20 @@ pub trait EventListener: Send + Sync {
         &self,
         mut receiver: broadcast::Receiver<AppEvent>,
     ) -> Result<(), EventEmitterError> {
-        while let Ok(event) = receiver.recv().await {
-            if let Err(e) = self.handle_event(event).await {
-                warn!("Error handling event: {:?}", e);
+        loop {
+            match receiver.recv().await {
+                Ok(event) => {
+                    if let Err(e) = self.handle_event(event).await {
+                        warn!("Error handling event: {:?}", e);
+                    }
+                }
+                // Falling behind loses the skipped events, but not the listener.
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    warn!("Listener fell behind and skipped {} events", skipped);
+                }
+                Err(broadcast::error::RecvError::Closed) => return Ok(()),
             }
         }
-        Ok(())
     }
 
     async fn handle_event(&self, event: AppEvent) -> Result<(), EventEmitterError>;
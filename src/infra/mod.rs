@@ -2,3 +2,4 @@ pub mod ba;
 pub mod error;
 pub mod event_emmiter;
 pub mod server;
+pub mod session;
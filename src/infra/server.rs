@@ -13,11 +13,19 @@ use crate::{
         client::service::ClientService, game::service::GameService, room::service::RoomService,
     },
     presentation::{
-        http::{asset, client::auth, room::new_room_handler},
+        http::{
+            asset,
+            client::auth,
+            daily::daily_leaderboard_handler,
+            room::{list_rooms_handler, new_room_handler, new_room_with_body_handler},
+            schema::client_message_schema,
+        },
         ws::handler::ws_handler,
     },
 };
 
+use super::session::Sessions;
+
 pub struct Server {
     host: String,
     port: u16,
@@ -27,11 +35,15 @@ pub struct Server {
 
 pub struct AppState {
     pub is_production: bool,
+    pub sessions: Sessions,
 }
 
 impl AppState {
     pub fn new(is_production: bool) -> Self {
-        Self { is_production }
+        Self {
+            is_production,
+            sessions: Sessions::default(),
+        }
     }
 }
 
@@ -57,8 +69,14 @@ impl Server {
 
         let api_routes = axum::Router::new()
             .route("/health", get(health_check))
-            .route("/new", get(new_room_handler))
+            .route(
+                "/new",
+                get(new_room_handler).post(new_room_with_body_handler),
+            )
+            .route("/rooms", get(list_rooms_handler))
+            .route("/daily", get(daily_leaderboard_handler))
             .route("/auth", get(auth))
+            .route("/schema", get(client_message_schema))
             .route("/ws", get(ws_handler));
 
         let app_state = Arc::new(AppState::new(self.is_production));
@@ -79,7 +97,7 @@ impl Server {
         tracing::debug!("Listening on {}", &addr);
 
         axum::Server::bind(&addr)
-            .serve(app.into_make_service())
+            .serve(app.into_make_service_with_connect_info::<SocketAddr>())
             .await
             .unwrap();
     }
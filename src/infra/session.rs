@@ -0,0 +1,43 @@
+use std::sync::Arc;
+
+use axum_extra::extract::CookieJar;
+use dashmap::{DashMap, DashSet};
+use rand::Rng;
+
+pub const SESSION_COOKIE: &str = "session";
+/// Random ids tried before giving up on finding a free one.
+const ID_ATTEMPTS: usize = 64;
+
+/// The sessions handed out by `/api/auth`. A session is an unguessable token
+/// the server maps to the client id it uses internally, so clients can't pick
+/// their own id or take over someone else's.
+#[derive(Debug, Clone, Default)]
+pub struct Sessions {
+    clients: Arc<DashMap<String, u16>>, // token -> client_id
+    taken: Arc<DashSet<u16>>,
+}
+
+impl Sessions {
+    /// The client id of the session cookie in `jar`, if the server issued it.
+    pub fn client_id(&self, jar: &CookieJar) -> Option<u16> {
+        let token = jar.get(SESSION_COOKIE)?;
+        self.clients.get(token.value()).map(|id| *id)
+    }
+
+    /// Starts a session for a new client and returns its token, or `None` if no
+    /// client id is free.
+    pub fn issue(&self) -> Option<String> {
+        let mut rng = rand::thread_rng();
+        let client_id = (0..ID_ATTEMPTS)
+            .map(|_| rng.gen::<u16>())
+            .find(|id| self.taken.insert(*id))?;
+
+        let token: String = rng
+            .gen::<[u8; 16]>()
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect();
+        self.clients.insert(token.clone(), client_id);
+        Some(token)
+    }
+}
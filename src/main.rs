@@ -2,7 +2,8 @@ use std::sync::Arc;
 
 use crate::config::Configuration;
 use application::{
-    client::service::ClientService, game::service::GameService, room::service::RoomService,
+    client::service::ClientService, game::service::GameService,
+    matchmaking::service::MatchmakingService, room::service::RoomService,
 };
 use domain::events::Topic;
 use infra::{error::Error, event_emmiter::EventEmitter, server::Server};
@@ -54,6 +55,10 @@ async fn main() -> Result<(), Error> {
     let event_emitter = Arc::new(EventEmitter::new());
     let room_service = RoomService::new(event_emitter.clone());
     let client_service = ClientService::new(event_emitter.clone());
+    let matchmaking_service = MatchmakingService::new(event_emitter.clone());
+    room_service.spawn_reconnect_sweeper();
+    room_service.spawn_vote_sweeper();
+    matchmaking_service.spawn_matchmaker();
 
     let _ = event_emitter
         .register_listener(room_service.clone(), Topic::RoomService)
@@ -61,6 +66,9 @@ async fn main() -> Result<(), Error> {
     let _ = event_emitter
         .register_listener(client_service.clone(), Topic::ClientService)
         .await;
+    let _ = event_emitter
+        .register_listener(matchmaking_service, Topic::Matchmaking)
+        .await;
 
     let game_controller =
         GameService::<ClientService, RoomService>::new(client_service, room_service, event_emitter);
@@ -1,15 +1,31 @@
-use axum::http::StatusCode;
-use axum_extra::extract::{cookie::Cookie, CookieJar};
-
-pub async fn auth(jar: CookieJar) -> Result<CookieJar, StatusCode> {
-    let mut new_jar = jar.clone();
-    if new_jar.get("client_id").is_none() {
-        let new_id: u16 = rand::random();
-        let new_id_str = new_id.to_string();
-        let mut cookie = Cookie::new("client_id", new_id_str);
-        cookie.set_path("/");
-        new_jar = new_jar.add(cookie);
+use std::sync::Arc;
+
+use axum::{http::StatusCode, Extension};
+use axum_extra::extract::{
+    cookie::{Cookie, SameSite},
+    CookieJar,
+};
+
+use crate::infra::{server::AppState, session::SESSION_COOKIE};
+
+/// Hands out a session cookie, unless the request already carries a live one.
+pub async fn auth(
+    Extension(state): Extension<Arc<AppState>>,
+    jar: CookieJar,
+) -> Result<CookieJar, StatusCode> {
+    if state.sessions.client_id(&jar).is_some() {
+        return Ok(jar);
     }
 
-    Ok(new_jar)
+    let token = state
+        .sessions
+        .issue()
+        .ok_or(StatusCode::SERVICE_UNAVAILABLE)?;
+    let mut cookie = Cookie::new(SESSION_COOKIE, token);
+    cookie.set_path("/");
+    cookie.set_http_only(true);
+    cookie.set_same_site(SameSite::Lax);
+    cookie.set_secure(state.is_production);
+
+    Ok(jar.add(cookie))
 }
@@ -0,0 +1,59 @@
+use axum::{extract::Query, http::StatusCode, response::IntoResponse, Extension, Json};
+
+use crate::{
+    application::{
+        client::service::ClientService, game::service::GameService, room::service::RoomService,
+    },
+    domain::{
+        events::{Command, CommandResult, Topic},
+        game::daily::today,
+        leaderboard::LEADERBOARD_DAYS,
+    },
+};
+
+#[derive(serde::Deserialize, Debug, Clone)]
+pub struct DailyQuery {
+    day: Option<u64>, // days since the unix epoch, today by default
+}
+
+/// Serves the fastest solves of a day's daily puzzle.
+pub async fn daily_leaderboard_handler(
+    Extension(game_service): Extension<GameService<ClientService, RoomService>>,
+    Query(query): Query<DailyQuery>,
+) -> impl IntoResponse {
+    let today = today();
+    let day = query.day.unwrap_or(today);
+    // Only the days still on the leaderboard can be asked for.
+    if day > today || day + LEADERBOARD_DAYS <= today {
+        return (
+            StatusCode::BAD_REQUEST,
+            format!(
+                "day must be between {} and {}",
+                today + 1 - LEADERBOARD_DAYS,
+                today
+            ),
+        )
+            .into_response();
+    }
+
+    match game_service
+        .event_emitter
+        .emit_command(Topic::RoomService, Command::GetDailyLeaderboard(day))
+        .await
+    {
+        Ok(CommandResult::DailyLeaderboard(leaderboard)) => {
+            (StatusCode::OK, Json(leaderboard)).into_response()
+        }
+        Ok(result) => {
+            tracing::error!(
+                "Unexpected result when fetching the daily leaderboard: {:?}",
+                result
+            );
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+        Err(e) => {
+            tracing::error!("Failed to emit command: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
@@ -1,3 +1,5 @@
 pub mod asset;
 pub mod client;
+pub mod daily;
 pub mod room;
+pub mod schema;
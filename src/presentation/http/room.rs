@@ -1,4 +1,12 @@
-use axum::{extract::Query, http::StatusCode, response::IntoResponse, Extension, Json};
+use std::sync::Arc;
+
+use axum::{
+    extract::{rejection::JsonRejection, Query},
+    http::StatusCode,
+    response::IntoResponse,
+    Extension, Json,
+};
+use axum_extra::extract::CookieJar;
 
 use crate::{
     application::{
@@ -6,26 +14,102 @@ use crate::{
     },
     domain::{
         events::{Command, CommandResult, Topic},
-        game::game::GameMode,
+        game::{
+            config::{GameConfig, HintPolicy, PenaltyPolicy},
+            game::GameMode,
+        },
+        room::RoomSettings,
+        vote::VoteThreshold,
     },
+    infra::server::AppState,
 };
 
 #[derive(serde::Deserialize, serde::Serialize, Debug, Clone)]
 pub struct NewGameQuery {
     mode: Option<String>,
+    target: Option<i64>, // winning score in race mode
+    max_players: Option<usize>,
+    late_join: Option<bool>,
+    late_join_score: Option<i64>,
+    passphrase: Option<String>, // refused, URLs end up in logs and browser history
+    invite_only: Option<bool>,
+    public: Option<bool>,
+    penalty: Option<String>,
+    penalty_points: Option<i64>,
+    lockout_secs: Option<u64>,
+    auto_deal: Option<bool>,
+    vote: Option<String>,
+    hints: Option<String>,
+    hint_limit: Option<u32>,
+    hint_cost: Option<i64>,
+    seed: Option<u64>,
+}
+
+/// The body of `POST /api/new`, for the options that must stay out of the URL.
+/// Unknown fields are refused, so a misspelt passphrase can't create an open
+/// room.
+#[derive(serde::Deserialize, Debug, Clone, Default)]
+#[serde(deny_unknown_fields)]
+pub struct NewGameBody {
+    passphrase: Option<String>,
 }
 
 #[derive(serde::Serialize)]
 struct RoomResponse {
     room_code: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    invite_code: Option<String>,
     error: Option<String>,
 }
 
 #[axum::debug_handler]
 pub async fn new_room_handler(
     Extension(game_service): Extension<GameService<ClientService, RoomService>>,
+    Extension(state): Extension<Arc<AppState>>,
+    jar: CookieJar,
+    Query(query): Query<NewGameQuery>,
+) -> impl IntoResponse {
+    // Whoever creates the room hosts it.
+    let host = state.sessions.client_id(&jar);
+    create_room(game_service, host, query, NewGameBody::default()).await
+}
+
+/// Creates a room with the options of the JSON body, e.g. a passphrase. A body
+/// that can't be read is refused rather than ignored.
+#[axum::debug_handler]
+pub async fn new_room_with_body_handler(
+    Extension(game_service): Extension<GameService<ClientService, RoomService>>,
+    Extension(state): Extension<Arc<AppState>>,
+    jar: CookieJar,
     Query(query): Query<NewGameQuery>,
+    body: Result<Json<NewGameBody>, JsonRejection>,
 ) -> impl IntoResponse {
+    let host = state.sessions.client_id(&jar);
+    match body {
+        Ok(Json(body)) => create_room(game_service, host, query, body).await,
+        Err(rejection) => (
+            StatusCode::BAD_REQUEST,
+            Json(RoomResponse::new(None, Some(rejection.body_text()))),
+        ),
+    }
+}
+
+async fn create_room(
+    game_service: GameService<ClientService, RoomService>,
+    host: Option<u16>,
+    query: NewGameQuery,
+    body: NewGameBody,
+) -> (StatusCode, Json<RoomResponse>) {
+    if query.passphrase.is_some() {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(RoomResponse::new(
+                None,
+                Some("passphrase must be sent in the request body".to_string()),
+            )),
+        );
+    }
+
     let mode_str = query.mode.unwrap_or_else(|| "classic".to_string());
 
     let mode = match mode_str.parse::<GameMode>() {
@@ -41,10 +125,99 @@ pub async fn new_room_handler(
         }
     };
 
+    if mode == GameMode::Daily {
+        // Every daily solve is ranked against everyone else's, so the rules
+        // that would make one run easier than another stay fixed.
+        let error = if query.seed.is_some() {
+            Some("seed is not supported in daily mode")
+        } else if query.hints.is_some() || query.hint_limit.is_some() || query.hint_cost.is_some() {
+            Some("hints are not supported in daily mode")
+        } else if query.penalty.is_some()
+            || query.penalty_points.is_some()
+            || query.lockout_secs.is_some()
+        {
+            Some("penalty is not supported in daily mode")
+        } else if query.auto_deal.is_some() {
+            Some("auto_deal is not supported in daily mode")
+        } else if query
+            .max_players
+            .is_some_and(|max_players| max_players != 1)
+        {
+            Some("the daily puzzle is played solo")
+        } else {
+            None
+        };
+        if let Some(error) = error {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(RoomResponse::new(None, Some(error.to_string()))),
+            );
+        }
+    }
+
+    let config = match GameConfig::for_mode(&mode, query.target).and_then(|config| {
+        let penalty = PenaltyPolicy::parse(
+            query.penalty.as_deref(),
+            query.penalty_points,
+            query.lockout_secs,
+        )?;
+        let hints = HintPolicy::parse(query.hints.as_deref(), query.hint_limit, query.hint_cost)?;
+        Ok(GameConfig {
+            penalty,
+            hints,
+            seed: query.seed,
+            auto_deal: query.auto_deal.unwrap_or(false),
+            ..config
+        })
+    }) {
+        Ok(config) => config,
+        Err(error) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(RoomResponse::new(None, Some(error))),
+            )
+        }
+    };
+
+    let vote_threshold = match query.vote.as_deref().map(str::parse::<VoteThreshold>) {
+        None => VoteThreshold::default(),
+        Some(Ok(vote_threshold)) => vote_threshold,
+        Some(Err(error)) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(RoomResponse::new(None, Some(error.to_string()))),
+            )
+        }
+    };
+
+    let defaults = RoomSettings::default();
+    let default_max_players = match mode {
+        GameMode::Daily => 1,
+        _ => defaults.max_players,
+    };
+    let settings = RoomSettings {
+        max_players: query.max_players.unwrap_or(default_max_players),
+        allow_late_join: query.late_join.unwrap_or(defaults.allow_late_join),
+        late_join_score: query.late_join_score.unwrap_or(defaults.late_join_score),
+        passphrase: body.passphrase,
+        invite_only: query.invite_only.unwrap_or(defaults.invite_only),
+        public: query.public.unwrap_or(defaults.public),
+        vote_threshold,
+    };
+    if let Err(error) = settings.validate() {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(RoomResponse::new(None, Some(error))),
+        );
+    }
+
     let event_emitter = &game_service.event_emitter;
 
     let command_result = match event_emitter
-        .emit_command(Topic::RoomService, Command::CreateRoom(mode))
+        .emit_command(
+            Topic::RoomService,
+            Command::CreateRoom(mode, config, settings, host),
+        )
         .await
     {
         Ok(result) => result,
@@ -61,9 +234,12 @@ pub async fn new_room_handler(
     };
 
     match command_result {
-        CommandResult::RoomCreated(room_code) => (
+        CommandResult::RoomCreated(room_code, invite_code) => (
             StatusCode::CREATED,
-            Json(RoomResponse::new(Some(room_code), None)),
+            Json(RoomResponse {
+                invite_code,
+                ..RoomResponse::new(Some(room_code), None)
+            }),
         ),
         CommandResult::NotHandled => (
             StatusCode::INTERNAL_SERVER_ERROR,
@@ -88,6 +264,31 @@ pub async fn new_room_handler(
 
 impl RoomResponse {
     pub fn new(room_code: Option<String>, error: Option<String>) -> Self {
-        Self { room_code, error }
+        Self {
+            room_code,
+            invite_code: None,
+            error,
+        }
+    }
+}
+
+/// Lists the public rooms that are still open to new players.
+pub async fn list_rooms_handler(
+    Extension(game_service): Extension<GameService<ClientService, RoomService>>,
+) -> impl IntoResponse {
+    match game_service
+        .event_emitter
+        .emit_command(Topic::RoomService, Command::ListPublicRooms)
+        .await
+    {
+        Ok(CommandResult::PublicRooms(rooms)) => (StatusCode::OK, Json(rooms)).into_response(),
+        Ok(result) => {
+            tracing::error!("Unexpected result when listing rooms: {:?}", result);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+        Err(e) => {
+            tracing::error!("Failed to emit command: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
     }
 }
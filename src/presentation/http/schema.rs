@@ -0,0 +1,10 @@
+use axum::{response::IntoResponse, Json};
+use schemars::schema_for;
+
+use crate::domain::message::IncomingMessage;
+
+/// JSON schema of the messages clients send over the WebSocket, for generating
+/// frontend and bot types.
+pub async fn client_message_schema() -> impl IntoResponse {
+    Json(schema_for!(IncomingMessage))
+}
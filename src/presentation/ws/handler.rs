@@ -1,22 +1,45 @@
-use axum::{extract::WebSocketUpgrade, response::IntoResponse, Extension};
+use std::{net::SocketAddr, sync::Arc};
+
+use axum::{
+    extract::{ConnectInfo, Query, WebSocketUpgrade},
+    response::IntoResponse,
+    Extension,
+};
 use axum_extra::extract::CookieJar;
+use serde::Deserialize;
 
 use crate::{
     application::{
         client::service::ClientService, game::service::GameService, room::service::RoomService,
     },
-    infra::error::{AppError, Error},
+    domain::game::compact::CardEncoding,
+    infra::{
+        error::{AppError, Error},
+        server::AppState,
+    },
 };
 
+#[derive(Deserialize)]
+pub struct WsQuery {
+    /// `compact` sends every card as a single index instead of an object.
+    #[serde(default)]
+    cards: CardEncoding,
+}
+
 pub async fn ws_handler(
     ws: WebSocketUpgrade,
     jar: CookieJar,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    Query(query): Query<WsQuery>,
     Extension(game_service): Extension<GameService<ClientService, RoomService>>,
+    Extension(state): Extension<Arc<AppState>>,
 ) -> Result<impl IntoResponse, AppError> {
     tracing::info!("Starting WebSocket connection");
-    match get_client_id_from_cookies(&jar) {
+    match state.sessions.client_id(&jar).ok_or(Error::ClientIdMissing) {
         Ok(client_id) => Ok(ws.on_upgrade(move |socket| async move {
-            game_service.start(client_id, socket).await;
+            game_service
+                .start(client_id, Some(peer.ip()), socket, query.cards)
+                .await;
         })),
         Err(err) => {
             tracing::error!("Failed to get client ID: {}", err);
@@ -24,9 +47,3 @@ pub async fn ws_handler(
         }
     }
 }
-
-fn get_client_id_from_cookies(jar: &CookieJar) -> Result<u16, Error> {
-    jar.get("client_id")
-        .and_then(|cookie| cookie.value().parse::<u16>().ok())
-        .ok_or(Error::ClientIdMissing)
-}
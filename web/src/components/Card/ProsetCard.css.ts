@@ -0,0 +1,38 @@
+import { style } from "@vanilla-extract/css";
+import { recipe } from "@vanilla-extract/recipes";
+import { vars } from "@styles/index.css";
+
+export const dotGrid = style({
+  display: "grid",
+  gridTemplateColumns: "repeat(2, 1fr)",
+  gridTemplateRows: "repeat(3, 1fr)",
+  gap: vars.sizes.s1,
+  width: "100%",
+  height: "100%",
+  padding: vars.sizes.s3,
+  justifyItems: "center",
+  alignItems: "center",
+});
+
+export const dotStyles = recipe({
+  base: {
+    width: "70%",
+    aspectRatio: "1",
+    borderRadius: "50%",
+  },
+
+  variants: {
+    // One color per bit of `dots`, in bit order.
+    color: {
+      red: { backgroundColor: "rgb(248, 80, 62)" },
+      orange: { backgroundColor: "rgb(244, 162, 97)" },
+      yellow: { backgroundColor: "rgb(233, 196, 106)" },
+      green: { backgroundColor: "rgb(61, 119, 115)" },
+      blue: { backgroundColor: "rgb(42, 157, 143)" },
+      purple: { backgroundColor: "rgb(65, 78, 155)" },
+    },
+    hidden: {
+      true: { visibility: "hidden" },
+    },
+  },
+});
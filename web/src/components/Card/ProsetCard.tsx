@@ -0,0 +1,52 @@
+import * as React from "react";
+import { ProsetCard as ProsetCardType } from "../../types";
+import { cardStyles as styles } from "./Card.css";
+import { dotGrid, dotStyles } from "./ProsetCard.css";
+import { cx } from "../../util/cx";
+
+type Props = {
+  card: ProsetCardType;
+  onClick?: () => void;
+  selected?: boolean;
+};
+
+const DOT_COLORS = [
+  "red",
+  "orange",
+  "yellow",
+  "green",
+  "blue",
+  "purple",
+] as const;
+
+// A ProSET card keeps every dot in its place, so equal colors line up across
+// the board and sets are easier to spot.
+export default function ProsetCard(props: Props): React.ReactElement {
+  const { card, onClick, selected } = props;
+
+  return (
+    <div
+      className={cx(
+        styles.card,
+        selected && styles.selected,
+        !onClick && styles.thumbnail,
+      )}
+      onClick={onClick}
+      onKeyDown={(e) => e.key === "Enter" && onClick && onClick()}
+      role="button"
+      tabIndex={0}
+    >
+      <div className={dotGrid}>
+        {DOT_COLORS.map((color, bit) => (
+          <span
+            key={color}
+            className={dotStyles({
+              color,
+              hidden: (card.dots & (1 << bit)) === 0,
+            })}
+          />
+        ))}
+      </div>
+    </div>
+  );
+}
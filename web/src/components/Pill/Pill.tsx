@@ -175,7 +175,7 @@ const Players: FC<PropsWithChildren<{ players: Player[] }>> = ({ players }) => {
     <AvatarGroup
       visible={2}
       items={topScoredPlayers.map((tp) => ({
-        image: <BoringAvatar variant="beam" size={40} name={tp?.client_id} colors={["#264653", "#2a9d8f", "#e9c46a", "#f4a261", "#e76f51"]} />,
+        image: <BoringAvatar variant="beam" size={40} name={tp?.name} colors={["#264653", "#2a9d8f", "#e9c46a", "#f4a261", "#e76f51"]} />,
         fallback: tp.name.substring(0, 1),
         alt: `${tp.name}'s avatar`,
         popoverContent: <AvatarTooltipContent player={tp} />,
@@ -203,7 +203,7 @@ const AvatarTooltipContent: FC<PropsWithChildren<AvatarProps>> = ({
         <Avatar
           alt={`${player.name}'s avatar`}
           fallback={player.name.substring(0, 1)}
-          image={<BoringAvatar variant="beam" size={40} name={player?.client_id} colors={["#264653", "#2a9d8f", "#e9c46a", "#f4a261", "#e76f51"]} />}
+          image={<BoringAvatar variant="beam" size={40} name={player?.name} colors={["#264653", "#2a9d8f", "#e9c46a", "#f4a261", "#e76f51"]} />}
         />
         <p style={{ fontWeight: 500 }}>{player.name}</p>
       </Box>
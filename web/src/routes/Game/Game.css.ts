@@ -1,4 +1,5 @@
 import { style } from "@vanilla-extract/css";
+import { vars } from "@styles/index.css";
 
 export const gamePageStyles = style({
   height: "100vh",
@@ -10,3 +11,10 @@ export const gamePageStyles = style({
   flexDirection: "column",
   position: "relative",
 });
+
+// Floats above the board while a ProSET selection is waiting to be submitted.
+export const submitMove = style({
+  position: "fixed",
+  bottom: vars.sizes.s6,
+  zIndex: 2,
+});
@@ -12,8 +12,10 @@ import { vars } from "@styles/index.css";
 import { useGameManager } from "@services/gameService";
 import { useRoomManager } from "@services/roomService";
 import { useAppSettings } from "@services/appSettingsService";
-import { GameMenuAction } from "@types";
+import { GameMenuAction, GameState } from "@types";
+import { WaitingRoom } from "@views/WaitingRoom/WaitingRoom";
 import { useGLTF } from "@react-three/drei";
+import Button from "@components/Button/Button";
 
 useGLTF.preload("/star.gltf");
 
@@ -25,6 +27,8 @@ export default function Game() {
     addCardToSelection,
     removeCardFromSelection,
     selectedCardIndexes,
+    makeMove,
+    canSubmitMove,
     activeNotifications,
     websocketStatus,
   } = useGameManager();
@@ -68,6 +72,9 @@ export default function Game() {
   const routeState = () => {
     return gameData.game_over ? (
       <GameEnded />
+    ) : gameData.state === GameState.WaitingForPlayers ||
+      gameData.state === GameState.Starting ? (
+      <WaitingRoom username={activeRoom?.username} />
     ) : !gameData.in_play?.length ? (
       <div
         style={{
@@ -90,6 +97,11 @@ export default function Game() {
           removeCardFromSelection={removeCardFromSelection}
           selectedCardIndexes={selectedCardIndexes}
         />
+        {canSubmitMove && (
+          <div className={styles.submitMove}>
+            <Button onClick={makeMove}>Submit set</Button>
+          </div>
+        )}
         <Pill
           activeNotifications={activeNotifications}
           websocketStatus={websocketStatus}
@@ -23,6 +23,13 @@ export const ACTIONS: readonly LobbyActions[] = [
     type: "new",
     mode: GameMode.Bestof3,
   },
+  {
+    title: "Race to 5",
+    description: "First to find 5 sets wins. No waiting for the deck to run out!",
+    image: "/images/bestof3_thumb.jpg",
+    type: "new",
+    mode: GameMode.Race,
+  },
   {
     title: "Join a game",
     description: "Join an ongoing game and make your mark!",
@@ -1,7 +1,7 @@
 import { useStore } from "effector-react";
 import { $webSocketStatus, sendAction } from "@store/websocket";
 import { useCallback, useEffect } from "react";
-import { GameAction, MessageType } from "@types";
+import { GameAction, GameMode, MessageType, Player } from "@types";
 import {
   $gameManager,
   addSelectedCard,
@@ -11,6 +11,22 @@ import {
 } from "@store/gameManager";
 import { $roomManager } from "@store/roomManager";
 
+// How many selected cards make a move. Ultra moves are two pairs of cards; a
+// ProSET set can have any number of cards, so those moves are sent by hand.
+const moveSize = (mode: GameMode): number | null => {
+  switch (mode) {
+    case GameMode.Ultra:
+      return 4;
+    case GameMode.Proset:
+      return null;
+    default:
+      return 3;
+  }
+};
+
+// The fewest cards that can form a ProSET set.
+const MIN_PROSET_MOVE = 3;
+
 export function useGameManager() {
   const { gameData, selectedCardIndexes, activeNotifications } =
     useStore($gameManager);
@@ -29,6 +45,63 @@ export function useGameManager() {
     }
   }, [activeRoom]);
 
+  const setReady = useCallback(
+    (ready: boolean) => {
+      if (activeRoom && activeRoom.code) {
+        sendAction({
+          type: MessageType.READY,
+          payload: { room_code: activeRoom.code, ready },
+        });
+      }
+    },
+    [activeRoom],
+  );
+
+  const startGame = useCallback(() => {
+    if (activeRoom && activeRoom.code) {
+      sendAction({
+        type: MessageType.START,
+        payload: { room_code: activeRoom.code },
+      });
+    }
+  }, [activeRoom]);
+
+  const kickPlayer = useCallback(
+    (playerId: Player["id"]) => {
+      if (activeRoom && activeRoom.code) {
+        sendAction({
+          type: MessageType.KICK,
+          payload: { room_code: activeRoom.code, player_id: playerId },
+        });
+      }
+    },
+    [activeRoom],
+  );
+
+  const lockRoom = useCallback(
+    (locked: boolean) => {
+      if (activeRoom && activeRoom.code) {
+        sendAction({
+          type: MessageType.LOCK,
+          payload: { room_code: activeRoom.code, locked },
+        });
+      }
+    },
+    [activeRoom],
+  );
+
+  const sendChatMessage = useCallback(
+    (message: string) => {
+      if (activeRoom && activeRoom.code) {
+        sendAction({
+          type: MessageType.CHAT,
+          payload: { room_code: activeRoom.code, message },
+        });
+      }
+    },
+    [activeRoom],
+  );
+
   const addCardToSelection = useCallback((cardIndex: number) => {
     addSelectedCard(cardIndex);
   }, []);
@@ -52,10 +125,24 @@ export function useGameManager() {
   }, [selectedCardIndexes, gameData.in_play, activeRoom]);
 
   useEffect(() => {
-    if (selectedCardIndexes.length === 3 && gameData.in_play) {
+    if (
+      selectedCardIndexes.length === moveSize(gameData.mode) &&
+      gameData.in_play
+    ) {
       makeMove();
     }
-  }, [selectedCardIndexes, gameData.in_play, activeRoom, makeMove]);
+  }, [
+    selectedCardIndexes,
+    gameData.in_play,
+    gameData.mode,
+    activeRoom,
+    makeMove,
+  ]);
+
+  // Moves without a fixed size wait for the player to submit them.
+  const canSubmitMove =
+    moveSize(gameData.mode) === null &&
+    selectedCardIndexes.length >= MIN_PROSET_MOVE;
 
   return {
     gameData,
@@ -63,7 +150,13 @@ export function useGameManager() {
     removeCardFromSelection,
     selectedCardIndexes,
     requestCards,
+    setReady,
+    startGame,
+    kickPlayer,
+    lockRoom,
+    sendChatMessage,
     makeMove,
+    canSubmitMove,
     activeNotifications,
     resetGameData,
     websocketStatus,
@@ -1,7 +1,9 @@
 import { createEffect, createEvent } from "effector";
-import { GameMode } from "@types";
+import { GameMode, MessageType } from "@types";
 import { $roomManager, setActiveRoom } from "@store/roomManager";
 import { resetGameData } from "@store/gameManager";
+import { sendAction } from "@store/websocket";
+import { resetChat } from "@store/chat";
 import { useStore } from "effector-react";
 
 const createNewRoom = createEffect(async (mode: GameMode) => {
@@ -38,8 +40,16 @@ const joinRoomEvent = createEvent<{
 const leaveRoomEvent = createEvent();
 
 leaveRoomEvent.watch(() => {
+  const { activeRoom } = $roomManager.getState();
+  if (activeRoom) {
+    sendAction({
+      type: MessageType.LEAVE,
+      payload: { room_code: activeRoom.code },
+    });
+  }
   setActiveRoom(null);
   resetGameData();
+  resetChat();
 });
 
 export function useRoomManager() {
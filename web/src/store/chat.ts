@@ -0,0 +1,11 @@
+import { createEvent, createStore } from "effector";
+import { ChatMessage } from "@types";
+
+export const chatMessageReceived = createEvent<ChatMessage>();
+export const chatHistoryReceived = createEvent<ChatMessage[]>();
+export const resetChat = createEvent();
+
+export const $chatMessages = createStore<ChatMessage[]>([])
+  .on(chatMessageReceived, (messages, message) => [...messages, message])
+  .on(chatHistoryReceived, (_, messages) => messages)
+  .reset(resetChat);
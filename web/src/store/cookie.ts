@@ -1,16 +1,14 @@
 import { createEffect, createEvent, createStore } from "effector";
 
-const COOKIE_NAME = "client_id";
-
 export const $hasClientId = createStore<boolean>(false);
 export const setCookie = createEvent<boolean>();
 
+// The session cookie is http-only, so ask every time: the server keeps a live
+// session and replaces one it doesn't know.
 export const auth = createEffect(async () => {
-  if (!document.cookie.split("; ").find((row) => row.startsWith(COOKIE_NAME))) {
-    await fetch(`/api/auth`, {
-      credentials: "include",
-    });
-  }
+  await fetch(`/api/auth`, {
+    credentials: "include",
+  });
   setCookie(true);
 });
 
@@ -5,9 +5,14 @@ import {
   forward,
   sample,
 } from "effector";
-import { Data, GameAction } from "@types";
-import { $gameManager, setGameData } from "./gameManager";
+import { Data, GameAction, ServerMessage, ServerMessageType } from "@types";
+import {
+  $gameManager,
+  displayNotificationWithTimer,
+  setGameData,
+} from "./gameManager";
 import { $hasClientId } from "./cookie";
+import { chatHistoryReceived, chatMessageReceived } from "./chat";
 
 export type WebSocketStatus = "IDLE" | "CONNECTING" | "OPEN" | "CLOSED";
 const RECONNECT_TIMEOUT = 3000;
@@ -25,6 +30,7 @@ export const setWebSocket = createEvent<WebSocket | null>();
 export const closeWebSocket = createEvent();
 export const openWebSocket = createEvent();
 export const messageReceived = createEvent<Data>();
+export const serverMessageReceived = createEvent<ServerMessage>();
 export const sendAction = createEvent<GameAction>();
 export const increaseRetryCount = createEvent<void>();
 
@@ -45,8 +51,8 @@ export const initializeWebSocket = createEffect<void, void, Error>({
     };
     socket.onmessage = (event) => {
       try {
-        const parsedData = JSON.parse(event.data);
-        messageReceived(parsedData);
+        const parsedData: ServerMessage = JSON.parse(event.data);
+        serverMessageReceived(parsedData);
       } catch (err) {
         console.error("error message", err);
       }
@@ -61,6 +67,52 @@ export const delayEffect = createEffect<void, void, Error>({
     ),
 });
 
+serverMessageReceived.watch((message) => {
+  const now = Date.now();
+  const timestamp = {
+    secs_since_epoch: Math.floor(now / 1000),
+    nanos_since_epoch: (now % 1000) * 1_000_000,
+  };
+
+  switch (message.type) {
+    case ServerMessageType.GAME_STATE:
+      messageReceived(message.payload);
+      break;
+    case ServerMessageType.MOVE_REJECTED:
+    case ServerMessageType.JOIN_ERROR:
+    case ServerMessageType.ERROR:
+    case ServerMessageType.INVALID_MESSAGE:
+      displayNotificationWithTimer({
+        timestamp,
+        content: message.payload.message,
+        icon: "⚠️",
+      });
+      break;
+    case ServerMessageType.CHAT:
+      chatMessageReceived(message.payload);
+      break;
+    case ServerMessageType.CHAT_HISTORY:
+      chatHistoryReceived(message.payload.messages);
+      break;
+    case ServerMessageType.KICKED:
+      displayNotificationWithTimer({
+        timestamp,
+        content: "You were removed from the room by the host",
+        icon: "🚪",
+      });
+      break;
+    case ServerMessageType.NOTICE:
+      displayNotificationWithTimer({
+        timestamp,
+        content: message.payload.message,
+        icon: "💬",
+      });
+      break;
+    default:
+      break;
+  }
+});
+
 $wsSocket.on(setWebSocket, (_, socket) => socket);
 $webSocketStatus.on(closeWebSocket, () => "CLOSED");
 $webSocketStatus.on(openWebSocket, () => "OPEN");
@@ -74,7 +126,8 @@ sample({
   target: setGameData,
   filter: (source, clock) =>
     JSON.stringify(source.gameData.in_play) !== JSON.stringify(clock.in_play) ||
-    JSON.stringify(source.gameData.players) !== JSON.stringify(clock.players),
+    JSON.stringify(source.gameData.players) !== JSON.stringify(clock.players) ||
+    source.gameData.state !== clock.state,
 });
 
 sample({
@@ -1,10 +1,13 @@
 import { COLORS, SHADINGS } from "./consts";
 
 export type Player = {
-  client_id: string;
+  id: number;
   name: string;
   request: boolean;
+  ready: boolean;
   score: number;
+  locked_until?: number | null;
+  hints_used?: number;
 };
 
 export type Card = {
@@ -14,24 +17,163 @@ export type Card = {
   shading: number;
 };
 
+// Bit i of `dots` is set when the dot of color i is on the card.
+export type ProsetCard = {
+  dots: number;
+};
+
+// Any card that can be on the board; ProSET games deal ProsetCards.
+export type BoardCard = Card | ProsetCard;
+
+export const isProsetCard = (card: BoardCard): card is ProsetCard =>
+  "dots" in card;
+
 export enum GameMode {
   Classic = "classic",
   Bestof3 = "bestof3",
+  Race = "race",
+  Daily = "daily",
+  Ultra = "ultra",
+  Junior = "junior",
+  Proset = "proset",
+}
+
+export enum GameState {
+  WaitingForPlayers = "WaitingForPlayers",
+  Starting = "Starting",
+  InProgress = "InProgress",
+  Ended = "Ended",
 }
 
+export type PenaltyPolicy =
+  | { type: "none" }
+  | { type: "deduction"; value: number }
+  | { type: "lockout"; value: number };
+
+export type HintPolicy =
+  | { type: "off" }
+  | { type: "limited"; value: number }
+  | { type: "cost"; value: number };
+
 export type Data = {
   game_over?: boolean;
-  in_play: Card[] | [];
+  state?: GameState;
+  countdown_ends_at?: number | null;
+  in_play: BoardCard[] | [];
   last_player: string | null;
-  last_set: Card[] | [];
+  last_set: BoardCard[] | [];
   players: Player[];
   remaining?: number;
+  sets_on_board?: number;
   mode: GameMode;
+  target_score?: number | null;
+  penalty?: PenaltyPolicy;
+  auto_deal?: boolean;
+  hints?: HintPolicy;
   events: Event[];
+  host?: Player["id"] | null;
+  locked?: boolean;
+  spectators?: number;
+  max_players?: number;
+  votes?: OpenVote[];
+  vote_threshold?: "majority" | "all_active" | "host_override";
+  seed?: number | null;
+  puzzle?: Puzzle | null;
+};
+
+export type Puzzle = {
+  day: number;
+  date: string;
+  sets: number;
+  found: Card[][];
+  started_at: number | null;
+  solve_time: number | null;
+};
+
+export type DailyLeaderboard = {
+  day: number;
+  date: string;
+  sets: number;
+  entries: { rank: number; name: string; time_ms: number }[];
+};
+
+export enum ServerMessageType {
+  GAME_STATE = "game_state",
+  MOVE_REJECTED = "move_rejected",
+  JOIN_ERROR = "join_error",
+  ACK = "ack",
+  NOTICE = "notice",
+  INVALID_MESSAGE = "invalid_message",
+  KICKED = "kicked",
+  CHAT = "chat",
+  CHAT_HISTORY = "chat_history",
+  LOBBY = "lobby",
+  MATCH_FOUND = "match_found",
+  HINT = "hint",
+  ERROR = "error",
+}
+
+export type ServerMessage =
+  | { type: ServerMessageType.GAME_STATE; payload: Data }
+  | {
+      type: ServerMessageType.MOVE_REJECTED;
+      payload: { request_id?: string; reason: string; message: string };
+    }
+  | {
+      type: ServerMessageType.JOIN_ERROR;
+      payload: {
+        request_id?: string;
+        room_code: string;
+        reason: string;
+        message: string;
+      };
+    }
+  | { type: ServerMessageType.ACK; payload: { request_id: string } }
+  | { type: ServerMessageType.NOTICE; payload: { message: string } }
+  | {
+      type: ServerMessageType.INVALID_MESSAGE;
+      payload: { request_id?: string; field: string; message: string };
+    }
+  | { type: ServerMessageType.KICKED; payload: { room_code: string } }
+  | { type: ServerMessageType.CHAT; payload: ChatMessage }
+  | {
+      type: ServerMessageType.CHAT_HISTORY;
+      payload: { room_code: string; messages: ChatMessage[] };
+    }
+  | { type: ServerMessageType.LOBBY; payload: { rooms: RoomSummary[] } }
+  | {
+      type: ServerMessageType.MATCH_FOUND;
+      payload: { room_code: string; players: number };
+    }
+  | {
+      type: ServerMessageType.HINT;
+      payload: { room_code: string; cards: BoardCard[] };
+    }
+  | {
+      type: ServerMessageType.ERROR;
+      payload: { request_id?: string; message: string };
+    };
+
+export type ChatMessage = {
+  player_id: Player["id"];
+  name: string;
+  message: string;
+  sent_at: number;
+};
+
+export type RoomSummary = {
+  room_code: string;
+  mode: GameMode;
+  state: GameState;
+  players: number;
+  max_players: number;
+  spectators: number;
+  passphrase_required: boolean;
+  age_secs: number;
 };
 
 export type Move = {
-  cards: Card[];
+  cards: BoardCard[];
   room_code: string;
 };
 
@@ -71,12 +213,27 @@ export enum MessageType {
   MOVE = "move",
   REQUEST = "request",
   RESET = "reset",
+  LEAVE = "leave",
+  READY = "ready",
+  START = "start",
+  KICK = "kick",
+  SET_MODE = "set_mode",
+  LOCK = "lock",
+  CHAT = "chat",
+  MUTE = "mute",
+  VOTE = "vote",
+  HINT = "hint",
+  WATCH_LOBBY = "watch_lobby",
+  QUICK_MATCH = "quick_match",
+  CANCEL_QUICK_MATCH = "cancel_quick_match",
+  PING = "ping",
   INIT = "init",
   CLOSE = "close",
 }
 
 interface BaseAction {
   type: MessageType;
+  request_id?: string;
   payload?: {
     room_code?: string;
     [key: string]: unknown;
@@ -88,6 +245,9 @@ export interface JoinGameAction extends BaseAction {
   payload: {
     room_code: string;
     player_username: string;
+    spectator?: boolean;
+    passphrase?: string;
+    invite_code?: string;
   };
 }
 
@@ -95,7 +255,7 @@ export interface MoveGameAction extends BaseAction {
   type: MessageType.MOVE;
   payload: {
     room_code: string;
-    cards: Card[];
+    cards: BoardCard[];
   };
 }
 
@@ -103,7 +263,134 @@ export interface RequestCardsAction extends BaseAction {
   type: MessageType.REQUEST;
 }
 
-export type GameAction = JoinGameAction | MoveGameAction | RequestCardsAction;
+export interface LeaveRoomAction extends BaseAction {
+  type: MessageType.LEAVE;
+  payload: {
+    room_code: string;
+  };
+}
+
+export interface ReadyAction extends BaseAction {
+  type: MessageType.READY;
+  payload: {
+    room_code: string;
+    ready: boolean;
+  };
+}
+
+export interface StartGameAction extends BaseAction {
+  type: MessageType.START;
+  payload: {
+    room_code: string;
+  };
+}
+
+export interface KickPlayerAction extends BaseAction {
+  type: MessageType.KICK;
+  payload: {
+    room_code: string;
+    player_id: Player["id"];
+  };
+}
+
+export interface SetModeAction extends BaseAction {
+  type: MessageType.SET_MODE;
+  payload: {
+    room_code: string;
+    mode: GameMode;
+  };
+}
+
+export interface LockRoomAction extends BaseAction {
+  type: MessageType.LOCK;
+  payload: {
+    room_code: string;
+    locked: boolean;
+  };
+}
+
+export interface ChatAction extends BaseAction {
+  type: MessageType.CHAT;
+  payload: {
+    room_code: string;
+    message: string;
+  };
+}
+
+export interface MutePlayerAction extends BaseAction {
+  type: MessageType.MUTE;
+  payload: {
+    room_code: string;
+    player_id: Player["id"];
+    muted: boolean;
+  };
+}
+
+export type VoteKind =
+  | { type: "add_cards" }
+  | { type: "rematch" }
+  | { type: "kick"; player_id: Player["id"] }
+  | { type: "change_mode"; mode: GameMode; target_score?: number };
+
+export type OpenVote = {
+  kind: VoteKind;
+  voters: Player["id"][];
+  needed: number;
+  expires_at: number;
+};
+
+export interface HintAction extends BaseAction {
+  type: MessageType.HINT;
+  payload: {
+    room_code: string;
+  };
+}
+
+export interface VoteAction extends BaseAction {
+  type: MessageType.VOTE;
+  payload: {
+    room_code: string;
+    vote: VoteKind;
+  };
+}
+
+export interface WatchLobbyAction extends BaseAction {
+  type: MessageType.WATCH_LOBBY;
+  payload: {
+    watching: boolean;
+  };
+}
+
+export interface QuickMatchAction extends BaseAction {
+  type: MessageType.QUICK_MATCH;
+  payload: {
+    mode: GameMode;
+    players: number;
+    player_username: string;
+  };
+}
+
+export interface CancelQuickMatchAction extends BaseAction {
+  type: MessageType.CANCEL_QUICK_MATCH;
+}
+
+export type GameAction =
+  | JoinGameAction
+  | MoveGameAction
+  | RequestCardsAction
+  | LeaveRoomAction
+  | ReadyAction
+  | StartGameAction
+  | KickPlayerAction
+  | SetModeAction
+  | LockRoomAction
+  | ChatAction
+  | MutePlayerAction
+  | VoteAction
+  | HintAction
+  | WatchLobbyAction
+  | QuickMatchAction
+  | CancelQuickMatchAction;
 
 export enum GameMenuAction {
   invite = "invite",
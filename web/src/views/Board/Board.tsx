@@ -1,17 +1,21 @@
 import * as React from "react";
-import { Card as CardType, Data } from "../../types";
+import { BoardCard, Data, isProsetCard } from "../../types";
 import Card from "@components/Card/Card";
+import ProsetCard from "@components/Card/ProsetCard";
 import { boardVars, boardStyles as styles } from "./Board.css";
 import { AnimatePresence, motion } from "framer-motion";
 import { assignInlineVars } from "@vanilla-extract/dynamic";
 import { useIsMobile } from "@hooks/useIsMobile";
 
-const createCardKey = (card: CardType): string => {
+const createCardKey = (card: BoardCard): string => {
+  if (isProsetCard(card)) {
+    return `proset-${card.dots}`;
+  }
   return `${card.color}-${card.shape}-${card.number}-${card.shading}`;
 };
 
 interface AnimatedCardProps {
-  card: CardType;
+  card: BoardCard;
   index: number;
   onClick: (index: number) => void;
   isSelected: boolean;
@@ -37,11 +41,19 @@ const AnimatedCard: React.FC<React.PropsWithChildren<AnimatedCardProps>> = ({
         }}
         style={{ position: "absolute", top: 0, left: 0 }}
       >
-        <Card
-          selected={isSelected}
-          onClick={() => onClick(index)}
-          card={card}
-        />
+        {isProsetCard(card) ? (
+          <ProsetCard
+            selected={isSelected}
+            onClick={() => onClick(index)}
+            card={card}
+          />
+        ) : (
+          <Card
+            selected={isSelected}
+            onClick={() => onClick(index)}
+            card={card}
+          />
+        )}
       </motion.div>
     </AnimatePresence>
   );
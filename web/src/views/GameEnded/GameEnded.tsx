@@ -62,7 +62,7 @@ export const GameEnded = () => {
           <h1>We have a winner!</h1>
         </Box>
         <Box gap={vars.sizes.s1} xAlign="center" yAlign="center">
-          <Avatar variant="beam" size={36} name={winner?.client_id} colors={["#264653", "#2a9d8f", "#e9c46a", "#f4a261", "#e76f51"]} />
+          <Avatar variant="beam" size={36} name={winner?.name} colors={["#264653", "#2a9d8f", "#e9c46a", "#f4a261", "#e76f51"]} />
           <p>{winner?.name}</p>
         </Box>
       </Box>
@@ -84,12 +84,12 @@ export const GameEnded = () => {
           <Box xAlign="center" gap={vars.sizes.s6} orientation="row">
             {top3.map((p: Player) => (
               <Box
-                key={p?.client_id}
+                key={p?.id}
                 gap={vars.sizes.s1}
                 xAlign="center"
                 yAlign="center"
               >
-                <Avatar variant="beam" size={36} name={p?.client_id} colors={["#264653", "#2a9d8f", "#e9c46a", "#f4a261", "#e76f51"]} />
+                <Avatar variant="beam" size={36} name={p?.name} colors={["#264653", "#2a9d8f", "#e9c46a", "#f4a261", "#e76f51"]} />
                 <Box yAlign="center" xAlign="center" gap={0}>
                   <p>{p?.name}</p>
                   <span
@@ -111,9 +111,14 @@ export const GameEnded = () => {
     );
   };
 
-  const message = {
+  const message: Record<GameMode, JSX.Element> = {
     [GameMode.Bestof3]: <BestOf3ModeMessage />,
     [GameMode.Classic]: <ClassicModeMessage />,
+    [GameMode.Race]: <BestOf3ModeMessage />,
+    [GameMode.Daily]: <ClassicModeMessage />,
+    [GameMode.Ultra]: <ClassicModeMessage />,
+    [GameMode.Junior]: <ClassicModeMessage />,
+    [GameMode.Proset]: <ClassicModeMessage />,
   };
 
   return (
@@ -0,0 +1,25 @@
+import { vars } from "@styles/index.css";
+import { style, globalStyle } from "@vanilla-extract/css";
+
+export const container = style({
+  zIndex: 1,
+  display: "flex",
+  flexDirection: "column",
+  justifyContent: "center",
+  alignItems: "center",
+  gap: vars.sizes.s6,
+});
+
+export const playerList = style({
+  display: "flex",
+  flexDirection: "column",
+  gap: vars.sizes.s2,
+  listStyle: "none",
+  padding: 0,
+  margin: 0,
+});
+
+globalStyle(`${container} h1`, {
+  ...vars.typography["3xl"],
+  fontWeight: 550,
+});
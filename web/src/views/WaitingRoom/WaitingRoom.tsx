@@ -0,0 +1,86 @@
+import { useEffect, useState } from "react";
+import { motion } from "framer-motion";
+import Box from "@components/Box/Box";
+import Button from "@components/Button/Button";
+import { vars } from "@styles/index.css";
+import { GameState, Player } from "@types";
+import { useGameManager } from "@services/gameService";
+import * as styles from "./WaitingRoom.css";
+
+const useCountdown = (endsAt?: number | null) => {
+  const [secondsLeft, setSecondsLeft] = useState<number | null>(null);
+
+  useEffect(() => {
+    if (!endsAt) {
+      setSecondsLeft(null);
+      return;
+    }
+    const tick = () =>
+      setSecondsLeft(Math.max(0, Math.ceil((endsAt - Date.now()) / 1000)));
+    tick();
+    const interval = setInterval(tick, 200);
+    return () => clearInterval(interval);
+  }, [endsAt]);
+
+  return secondsLeft;
+};
+
+export const WaitingRoom = ({ username }: { username?: string }) => {
+  const { gameData, setReady, startGame, kickPlayer, lockRoom } =
+    useGameManager();
+  const secondsLeft = useCountdown(gameData.countdown_ends_at);
+
+  const me = gameData.players.find((p: Player) => p.name === username);
+  const isStarting = gameData.state === GameState.Starting;
+  const isHost = me !== undefined && me.id === gameData.host;
+
+  return (
+    <motion.div
+      className={styles.container}
+      initial={{ opacity: 0, y: 50 }}
+      animate={{ opacity: 1, y: 0 }}
+      exit={{ opacity: 0, y: -60 }}
+    >
+      <h1>{isStarting ? `Starting in ${secondsLeft ?? ""}` : "Waiting for players"}</h1>
+      {gameData.max_players && (
+        <p>
+          {gameData.players.length} / {gameData.max_players} players
+        </p>
+      )}
+      <ul className={styles.playerList}>
+        {gameData.players.map((p: Player) => (
+          <li key={p.id}>
+            {p.ready ? "✅" : "⏳"} {p.name}
+            {p.id === gameData.host && " 👑"}
+            {isHost && p.id !== me?.id && (
+              <Button variant="outline" onClick={() => kickPlayer(p.id)}>
+                Kick
+              </Button>
+            )}
+          </li>
+        ))}
+      </ul>
+      {!!gameData.spectators && <p>👀 {gameData.spectators} watching</p>}
+      {!isStarting && (
+        <Box orientation="row" gap={vars.sizes.s4}>
+          <Button onClick={() => setReady(!me?.ready)}>
+            {me?.ready ? "Not ready" : "Ready"}
+          </Button>
+          {isHost && (
+            <>
+              <Button variant="outline" onClick={() => startGame()}>
+                Start now
+              </Button>
+              <Button
+                variant="outline"
+                onClick={() => lockRoom(!gameData.locked)}
+              >
+                {gameData.locked ? "Unlock room" : "Lock room"}
+              </Button>
+            </>
+          )}
+        </Box>
+      )}
+    </motion.div>
+  );
+};